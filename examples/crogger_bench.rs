@@ -1,14 +1,13 @@
 use clark::{
-    App, AppIdentity, AppVersion, Arg, ArgOptionValidator,
+    App, AppIdentity, AppVersion, Arg, SeededRng,
+    bench::{Bench, BenchReport, invoke_bench},
+    generate_seed, impl_arg_enum,
     log::{
         self, BwFormatter, ColorfulFormatter, Context, Emitter, Error, FileEmitter, Formatter,
         Logger, StderrEmitter, StdoutEmitter, ThreadedEmitter,
     },
 };
-use std::{
-    fmt,
-    time::{Duration, Instant, SystemTime},
-};
+use std::{fmt, time::Duration};
 
 #[derive(Default)]
 struct EmptyEmitter;
@@ -41,32 +40,45 @@ impl Formatter for EmptyFormatter {
     }
 }
 
-fn invoke_bench<T>(mut f: impl FnMut() -> T) -> (T, Duration) {
-    let begin = Instant::now();
-    let res = f();
-    let elapsed = begin.elapsed();
-    (res, elapsed)
+#[derive(Clone, Copy, Default)]
+enum FormatKind {
+    #[default]
+    Color,
+    Bw,
+    Plain,
+    Empty,
+}
+
+impl_arg_enum!(FormatKind {
+    Color => "color": "colorful formatting with metadata (default)",
+    Bw => "bw": "black and white formatting with metadata",
+    Plain => "plain": "message only",
+    Empty => "empty": "no formatting content",
+});
+
+#[derive(Clone, Copy, Default)]
+enum EmitKind {
+    #[default]
+    Stdout,
+    Stderr,
+    File,
+    Empty,
 }
 
+impl_arg_enum!(EmitKind {
+    Stdout => "stdout": "emit logs to stdout (default)",
+    Stderr => "stderr": "emit logs to stderr",
+    File => "file": "emit the logs to example.log",
+    Empty => "empty": "discard all emitted output",
+});
+
 fn random_string(len: usize) -> String {
     const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
-    let mut seed = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or(0)
-        ^ len as u64;
-
-    let mut next = || {
-        seed ^= seed >> 12;
-        seed ^= seed << 25;
-        seed ^= seed >> 27;
-        seed = seed.wrapping_mul(0x2545F4914F6CDD1D);
-        seed
-    };
+    let mut rng = SeededRng::new(generate_seed() ^ len as u64);
 
     let mut out = String::with_capacity(len);
     for _ in 0..len {
-        let idx = (next() as usize) % ALPHABET.len();
+        let idx = rng.next_below(ALPHABET.len() as u64) as usize;
         out.push(ALPHABET[idx] as char);
     }
     out
@@ -89,25 +101,25 @@ where
     }
 }
 
-fn create_logger(formatter: &str, emitter: &str, threaded: bool) -> Logger {
+fn create_logger(formatter: FormatKind, emitter: EmitKind, threaded: bool) -> Logger {
     let logger = Logger::default();
     let logger = match formatter {
-        "bw" => logger.set_formatter(BwFormatter),
-        "plain" => logger.set_formatter(PlainFormatter),
-        "empty" => logger.set_formatter(EmptyFormatter),
-        _ => logger.set_formatter(ColorfulFormatter),
+        FormatKind::Bw => logger.set_formatter(BwFormatter),
+        FormatKind::Plain => logger.set_formatter(PlainFormatter),
+        FormatKind::Empty => logger.set_formatter(EmptyFormatter),
+        FormatKind::Color => logger.set_formatter(ColorfulFormatter),
     };
     match (threaded, emitter) {
-        (false, "stderr") => logger.set_emitter(StderrEmitter),
-        (false, "empty") => logger.set_emitter(EmptyEmitter),
-        (false, "file") => logger.set_emitter(FileEmitter::open("example.log").unwrap()),
-        (false, _) => logger.set_emitter(StdoutEmitter),
-        (true, "stderr") => logger.set_emitter(ThreadedEmitter::new(StderrEmitter)),
-        (true, "empty") => logger.set_emitter(ThreadedEmitter::new(EmptyEmitter)),
-        (true, "file") => logger.set_emitter(ThreadedEmitter::new(
+        (false, EmitKind::Stderr) => logger.set_emitter(StderrEmitter),
+        (false, EmitKind::Empty) => logger.set_emitter(EmptyEmitter),
+        (false, EmitKind::File) => logger.set_emitter(FileEmitter::open("example.log").unwrap()),
+        (false, EmitKind::Stdout) => logger.set_emitter(StdoutEmitter),
+        (true, EmitKind::Stderr) => logger.set_emitter(ThreadedEmitter::new(StderrEmitter)),
+        (true, EmitKind::Empty) => logger.set_emitter(ThreadedEmitter::new(EmptyEmitter)),
+        (true, EmitKind::File) => logger.set_emitter(ThreadedEmitter::new(
             FileEmitter::open("example.log").unwrap(),
         )),
-        (true, _) => logger.set_emitter(ThreadedEmitter::new(StdoutEmitter)),
+        (true, EmitKind::Stdout) => logger.set_emitter(ThreadedEmitter::new(StdoutEmitter)),
     }
 }
 
@@ -144,13 +156,7 @@ fn main() {
         "--emit",
         Arg::new()
             .help("Emitter to use for output")
-            .validate(
-                ArgOptionValidator::new()
-                    .option("stdout", Some("emit logs to stdout (default)".to_string()))
-                    .option("stderr", Some("emit logs to stderr".to_string()))
-                    .option("file", Some("emit the logs to example.log".to_string()))
-                    .option("empty", Some("discard all emitted output".to_string())),
-            )
+            .value_enum::<EmitKind>()
             .optional(),
     );
     app.add_argument("--threaded", Arg::new().as_flag());
@@ -158,46 +164,40 @@ fn main() {
         "--format",
         Arg::new()
             .help("Formatter to use for each log message")
-            .validate(
-                ArgOptionValidator::new()
-                    .option(
-                        "color",
-                        Some("colorful formatting with metadata (default)".to_string()),
-                    )
-                    .option(
-                        "bw",
-                        Some("black and white formatting with metadata".to_string()),
-                    )
-                    .option("plain", Some("message only".to_string()))
-                    .option("empty", Some("no formatting content".to_string())),
-            )
+            .value_enum::<FormatKind>()
             .optional(),
     );
+    Bench::add_arguments(&mut app);
     app.add_help_arguments();
     app.parse_args(true);
 
     let args = app.args();
     let count = parse_or_default("count", args.first_of("--count"), 1_000_000u64);
     let msg_length = parse_or_default("msg_length", args.first_of("--msg_length"), 80u64);
-    let formatter = args
-        .first_of("--format")
-        .cloned()
-        .unwrap_or_else(|| "color".to_string());
-    let emitter = args
-        .first_of("--emit")
-        .cloned()
-        .unwrap_or_else(|| "stdout".to_string());
+    let formatter: FormatKind = args.get_enum("--format").unwrap_or_default();
+    let emitter: EmitKind = args.get_enum("--emit").unwrap_or_default();
 
     let message = random_string(msg_length as usize);
 
     log::warn!("Begin: Logger Init");
     let (logger, init_time) =
-        invoke_bench(|| create_logger(&formatter, &emitter, app.args().contains("--threaded")));
+        invoke_bench(|| create_logger(formatter, emitter, app.args().contains("--threaded")));
     log::warn!("End: Logger Init ({} ms)", init_time.as_millis());
 
+    let bench = match Bench::from_args("log message", &app) {
+        Ok(bench) => bench,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    };
     log::warn!("Begin: Log Message");
-    let (_, log_time) = invoke_bench(|| log_messages(&logger, &message, count));
-    log::warn!("End: Log Message ({} ms)", log_time.as_millis());
+    let summary = bench.run(|| log_messages(&logger, &message, count));
+    log::warn!("End: Log Message ({} ms)", summary.avg().as_millis());
+
+    let mut report = BenchReport::new();
+    report.add(summary);
+    report.print_with(&app);
 
     std::thread::sleep(Duration::from_secs(1));
 }
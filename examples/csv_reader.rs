@@ -1,4 +1,4 @@
-use clark::{App, AppIdentity, AppVersion, Arg, ArgEmptyValidator, ParseError};
+use clark::{App, AppIdentity, AppVersion, Arg, ArgEmptyValidator, ParseError, tui};
 use std::error::Error;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
@@ -31,10 +31,9 @@ fn main() -> Result<(), Box<dyn Error>> {
     app.add_help_arguments();
     app.parse_args(true);
 
-    let parsed = app.args();
+    let parsed = app.args_mut();
     let csv_path = parsed
-        .first_of("--csv")
-        .cloned()
+        .take_first_of("--csv")
         .ok_or_else(|| ParseError::invalid_value(format_args!("--csv is required")))?;
     let csv_path = PathBuf::from(csv_path);
     let show_headers = parsed.contains("--headers");
@@ -43,19 +42,17 @@ fn main() -> Result<(), Box<dyn Error>> {
         .map_err(|err| format!("failed to open {}: {err}", csv_path.display()))?;
     let reader = BufReader::new(file);
 
+    let mut table = tui::Table::new();
     for (line_idx, line) in reader.lines().enumerate() {
         let line = line?;
+        let row = line.split(',').map(|col| col.trim().to_string());
         if line_idx == 0 && show_headers {
-            println!("== {line} ==");
+            table = table.headers(row);
         } else {
-            let row = line
-                .split(',')
-                .map(|col| col.trim())
-                .collect::<Vec<_>>()
-                .join(" | ");
-            println!("{row}");
+            table = table.row(row);
         }
     }
+    print!("{}", app.render_out(&table.into()));
 
     Ok(())
 }
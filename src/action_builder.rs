@@ -1,10 +1,48 @@
-use crate::tui;
-use crate::{App, Arg, ArgOptionValidator, paragraph};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Instant,
+};
 
+use crate::{
+    App, Arg, ArgOptionValidator, ExitPolicy, ParseError, ParseOutcome, output, paragraph, tui,
+};
+
+pub type ActionResult = Result<(), Box<dyn Error>>;
+
+/* A failed action is rendered like a parse error and exits through the app's ExitPolicy. */
 pub trait ActionHandler {
-    fn run(&mut self, app: &mut App);
+    fn run(&mut self, app: &mut App) -> ActionResult;
+}
+
+/* What an action closure may return, () for actions that cannot fail. */
+pub trait IntoActionResult {
+    fn into_action_result(self) -> ActionResult;
+}
+
+impl IntoActionResult for () {
+    fn into_action_result(self) -> ActionResult {
+        Ok(())
+    }
+}
+
+impl<E: Into<Box<dyn Error>>> IntoActionResult for Result<(), E> {
+    fn into_action_result(self) -> ActionResult {
+        self.map_err(Into::into)
+    }
 }
 
+/* Lets small actions be registered inline without a struct per action. */
+impl<F: FnMut(&mut App) -> R, R: IntoActionResult> ActionHandler for F {
+    fn run(&mut self, app: &mut App) -> ActionResult {
+        self(app).into_action_result()
+    }
+}
+
+type BeforeHook = Box<dyn FnMut(&mut App, &str) -> ActionResult>;
+type AfterHook = Box<dyn FnMut(&mut App, &str, &ActionResult)>;
+
 struct AppAction {
     name: String,
     help_text: String,
@@ -15,6 +53,17 @@ pub struct ActionBuilder<'a> {
     app: &'a mut App,
     help_text: Option<String>,
     actions: Vec<AppAction>,
+    default_env: Option<String>,
+    default_config_key: Option<String>,
+    before: Vec<BeforeHook>,
+    after: Vec<AfterHook>,
+    external_prefix: Option<String>,
+}
+
+/* What an action name resolved to: a registered handler or an executable found on PATH. */
+enum Target<'b> {
+    Handler(&'b mut AppAction),
+    External(PathBuf),
 }
 
 impl<'a> ActionBuilder<'a> {
@@ -23,9 +72,51 @@ impl<'a> ActionBuilder<'a> {
             app,
             help_text,
             actions: Vec::new(),
+            default_env: None,
+            default_config_key: None,
+            before: Vec::new(),
+            after: Vec::new(),
+            external_prefix: None,
         }
     }
 
+    /*
+      Git-style plugins: an executable `<program>-<name>` on PATH becomes the action `name` and is
+      run with the remaining arguments, its exit code is what run returns.
+    */
+    pub fn external_subcommands(mut self) -> Self {
+        self.external_prefix = Some(format!("{}-", self.app.program_name()));
+        self
+    }
+
+    /* Runs ahead of the chosen handler, an Err skips the handler and becomes the action's result. */
+    pub fn before<R: IntoActionResult>(
+        mut self,
+        mut hook: impl FnMut(&mut App, &str) -> R + 'static,
+    ) -> Self {
+        self.before.push(Box::new(move |app, name| {
+            hook(app, name).into_action_result()
+        }));
+        self
+    }
+
+    /* Sees the action's result before a failure is rendered. */
+    pub fn after(mut self, hook: impl FnMut(&mut App, &str, &ActionResult) + 'static) -> Self {
+        self.after.push(Box::new(hook));
+        self
+    }
+
+    pub fn default_from_env(mut self, var: impl Into<String>) -> Self {
+        self.default_env = Some(var.into());
+        self
+    }
+
+    /* Looks the key up in the active config and profile values, e.g. `action = serve`. */
+    pub fn default_from_config(mut self, key: impl Into<String>) -> Self {
+        self.default_config_key = Some(key.into());
+        self
+    }
+
     pub fn add_action(
         mut self,
         name: impl Into<String>,
@@ -46,58 +137,247 @@ impl<'a> ActionBuilder<'a> {
         self
     }
 
-    pub fn run(self) {
+    /*
+      Parses the action tier and dispatches to the chosen handler without exiting. Ok carries the
+      exit code, 0 for a finished action or printed help and non-zero for a failed action, which
+      is rendered before returning. Parse errors are handed back unrendered.
+    */
+    pub fn run(mut self) -> Result<i32, ParseError> {
+        let res = self.dispatch();
+        output::flush();
+        res
+    }
+
+    /* The fire-and-forget form: parse errors go through the app's ExitPolicy and a failed action exits. */
+    pub fn run_or_exit(mut self) {
+        let res = self.dispatch();
+        let app = self.app;
+        match res {
+            Ok(0) => {}
+            Ok(code) => {
+                if app.exit_policy != ExitPolicy::Return && !app.has_io() {
+                    app.exit(code);
+                }
+            }
+            Err(err) => app.handle_error(err),
+        }
+    }
+
+    /* Records the run in the history with its real exit code, a parse error is not recorded. */
+    fn dispatch(&mut self) -> Result<i32, ParseError> {
+        let res = self.dispatch_action();
+        match &res {
+            Ok(code) => self.app.record_history(*code),
+            Err(_) => self.app.history_args = None,
+        }
+        res
+    }
+
+    fn dispatch_action(&mut self) -> Result<i32, ParseError> {
         if self.actions.is_empty() {
-            return;
+            return Ok(0);
         }
 
-        let ActionBuilder {
-            app,
-            help_text,
-            mut actions,
-        } = self;
+        let app = &mut *self.app;
+        let actions = &mut self.actions;
+        let help_text = self.help_text.take();
+        let default_env = self.default_env.take();
+        let default_config_key = self.default_config_key.take();
 
         let mut argument = Arg::new();
         if let Some(help) = help_text {
             argument = argument.help(help);
         }
         let mut options = ArgOptionValidator::new();
-        for action in &actions {
+        for action in actions.iter() {
             options = options.option(action.name.clone(), Some(action.help_text.clone()));
         }
+        let externals: Vec<(String, PathBuf)> = self
+            .external_prefix
+            .as_deref()
+            .map(find_externals)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(name, _)| actions.iter().all(|action| action.name != *name))
+            .collect();
+        for (name, path) in externals.iter() {
+            options = options.option(
+                name.clone(),
+                Some(format!("External command {}", path.display())),
+            );
+        }
         argument = argument.validate(options).required();
 
         app.add_positional_argument(argument);
+        app.tier_name("action");
         let action_index = app.arg_len() - 1;
 
-        app.parse_args(false);
+        if let Some(key) = &default_config_key {
+            app.reserved_config_keys.push(key.clone());
+        }
+        if app.multicall {
+            let program = app.program_name();
+            let applet = program.strip_suffix(".exe").unwrap_or(program).to_string();
+            if app.args().len() <= action_index
+                && actions.iter().any(|action| action.name == applet)
+            {
+                app.inject_after_program(applet);
+            }
+        }
+        if !parse_deferred(app)? {
+            return Ok(0);
+        }
+        if app.args().len() <= action_index
+            && let Some(action) = default_env
+                .and_then(|var| std::env::var(var).ok())
+                .or_else(|| default_config_key.and_then(|key| app.config_value(&key)))
+        {
+            app.inject_arg(action);
+            if !parse_deferred(app)? {
+                return Ok(0);
+            }
+        }
 
         if app.args().len() <= action_index {
-            eprintln!(
-                "{}",
-                tui::VStack(
-                    tui::Layout::default()
-                        .append_child(paragraph!("arg{}: expected action name", action_index))
-                        .style(tui::DomStyle::new().fg(tui::RgbColor::bright_yellow())),
-                )
-            );
-            std::process::exit(1)
+            let outcome = app.requested_outcome();
+            if app.print_outcome(outcome) {
+                return Ok(0);
+            }
+            return Err(ParseError::missing_required_argument(format_args!(
+                "expected action name"
+            ))
+            .key(format!("arg{}", action_index)));
         }
 
+        app.check_parsed()?;
+        app.print_warnings();
         let action_name = app.args().arg().to_string();
-        match actions.iter_mut().find(|action| action.name == action_name) {
-            Some(action) => action.handler.run(app),
-            None => {
-                eprintln!(
-                    "{}",
-                    &tui::VStack(
-                        tui::Layout::default()
-                            .append_child(paragraph!("Unknown action '{}'", action_name))
-                            .style(tui::DomStyle::new().fg(tui::RgbColor::bright_yellow())),
-                    )
-                );
-                std::process::exit(1)
+        let action_count = actions.len();
+        let target = match actions.iter_mut().find(|action| action.name == action_name) {
+            Some(action) => Target::Handler(action),
+            None => match externals.into_iter().find(|(name, _)| *name == action_name) {
+                Some((_, path)) => Target::External(path),
+                None => {
+                    return Err(ParseError::invalid_value(format_args!(
+                        "unknown action {}",
+                        action_name
+                    ))
+                    .key(format!("arg{}", action_index)));
+                }
+            },
+        };
+        let begin = Instant::now();
+        let mut code = 0;
+        let res = self
+            .before
+            .iter_mut()
+            .try_for_each(|hook| hook(app, &action_name))
+            .and_then(|()| match target {
+                Target::Handler(action) => action.handler.run(app),
+                Target::External(path) => run_external(&path, app).map(|c| code = c),
+            });
+        app.trace_phase("dispatch", begin.elapsed(), action_count);
+        for hook in self.after.iter_mut() {
+            hook(app, &action_name, &res);
+        }
+        match res {
+            Ok(()) => Ok(code),
+            Err(err) => {
+                app.render_action_error(&action_name, err.as_ref());
+                Ok(app.action_exit_code())
             }
         }
     }
 }
+
+/* Parses the action tier without the config check, false when there is nothing left to dispatch. */
+fn parse_deferred(app: &mut App) -> Result<bool, ParseError> {
+    app.defer_config_check = true;
+    let outcome = app.try_parse_args(false);
+    app.defer_config_check = false;
+    match outcome? {
+        ParseOutcome::Parsed => Ok(true),
+        outcome => {
+            app.print_outcome(outcome);
+            Ok(false)
+        }
+    }
+}
+
+/* Executables named prefix + name in PATH order, the first directory providing a name wins. */
+fn find_externals(prefix: &str) -> Vec<(String, PathBuf)> {
+    let mut found: Vec<(String, PathBuf)> = Vec::new();
+    let Some(path) = std::env::var_os("PATH") else {
+        return found;
+    };
+    for dir in std::env::split_paths(&path) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(prefix))
+                .map(|name| match cfg!(unix) {
+                    true => name,
+                    false => name.strip_suffix(".exe").unwrap_or(name),
+                })
+            else {
+                continue;
+            };
+            if !name.is_empty()
+                && is_executable(&path)
+                && found.iter().all(|(known, _)| known != name)
+            {
+                found.push((name.to_string(), path));
+            }
+        }
+    }
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    found
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| ext == "exe")
+}
+
+/* Hands every unparsed token to the external command and waits for it. */
+fn run_external(path: &Path, app: &mut App) -> Result<i32, Box<dyn Error>> {
+    let args: Vec<String> = app.raw_args.by_ref().collect();
+    let status = Command::new(path).args(args).status()?;
+    Ok(status.code().unwrap_or(1))
+}
+
+impl App {
+    pub(crate) fn render_action_error(&self, action: &str, err: &dyn Error) {
+        let mut layout = tui::Layout::default()
+            .append_child(paragraph!("{}: {}", action, err))
+            .style(tui::DomStyle::new().fg(self.theme.error));
+        if self.verbose_errors {
+            let mut cur = err.source();
+            while let Some(cause) = cur {
+                layout = layout.append_child(paragraph!("  caused by: {}", cause));
+                cur = cause.source();
+            }
+        }
+        self.write_err(&format!("{}\n", self.render_err(&tui::VStack(layout))));
+    }
+
+    /* ExitWithKind has no kind to go by for a handler's error, so a failed action gives 1. */
+    pub(crate) fn action_exit_code(&self) -> i32 {
+        match self.exit_policy {
+            ExitPolicy::ExitWith(code) => code,
+            ExitPolicy::ExitWithKind | ExitPolicy::Return => 1,
+        }
+    }
+}
@@ -1,24 +1,175 @@
-use std::iter::Peekable;
+use std::{
+    cell::RefCell,
+    iter::Peekable,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
-use crate::{AppIdentity, Arg, ArgParser, ArgValidator, ParsedArg, paragraph, tui};
+use crate::{
+    AppIdentity, AppIo, Arg, ArgKeyPrefixes, ArgKeyStyle, ArgParser, ArgPreprocessor, ArgValidator,
+    ConfigFile, CrossValidator, Doctor, HistoryFile, ParseError, ParseTimings, ParsedArg,
+    ResponseFileExpander, Session, Shell, output, paragraph, tui,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitPolicy {
+    ExitWith(i32),
+    ExitWithKind,
+    Return,
+}
+
+/* What a parse asked for, so embedders can render help or version output themselves. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseOutcome {
+    Parsed,
+    HelpRequested(usize),
+    HelpJsonRequested,
+    VersionRequested,
+    AboutRequested,
+    DebugConfigRequested,
+    /* A built-in command such as docs or completion already wrote its output. */
+    Handled,
+}
+
+impl Default for ExitPolicy {
+    fn default() -> Self {
+        Self::ExitWith(1)
+    }
+}
+
+type ParseExtension = Box<dyn FnOnce(&ParsedArg, &mut ArgParser)>;
 
 pub struct App {
     identity: AppIdentity,
-    parser: ArgParser,
+    pub(crate) parser: ArgParser,
     parsed: ParsedArg,
-    raw_args: Peekable<std::env::Args>,
+    pub(crate) raw_args: Peekable<std::vec::IntoIter<String>>,
+    pub(crate) argv: Vec<String>,
+    preprocessors: Vec<Box<dyn ArgPreprocessor>>,
+    cross_validators: Vec<Box<dyn CrossValidator>>,
+    extensions: Vec<ParseExtension>,
+    program_name: String,
+    trace_internals: bool,
+    pub(crate) docs_command: bool,
+    pub(crate) completion_command: bool,
+    pub(crate) config: Option<ConfigFile>,
+    pub(crate) config_path: Option<PathBuf>,
+    pub(crate) strict_config: bool,
+    pub(crate) reserved_config_keys: Vec<String>,
+    pub(crate) config_checked: bool,
+    pub(crate) defer_config_check: bool,
+    pub(crate) doctor: Option<Doctor>,
+    pub(crate) history: Option<HistoryFile>,
+    pub(crate) history_args: Option<Vec<String>>,
+    pub(crate) exit_policy: ExitPolicy,
+    error: Option<ParseError>,
+    warnings: Vec<String>,
+    quiet_flag: bool,
+    pub(crate) yes_flag: bool,
+    pub(crate) verbose_errors: bool,
+    version_flag: bool,
+    about_flag: bool,
+    pub(crate) debug_config_flag: bool,
+    pub(crate) multicall: bool,
+    color: tui::ColorChoice,
+    pub(crate) theme: tui::Theme,
+    pub(crate) io: Option<RefCell<AppIo>>,
+    pub(crate) seed: Option<u64>,
+    pub(crate) session: Option<Session>,
+    pub(crate) session_path: Option<PathBuf>,
 }
 
 impl App {
     pub fn new(identity: AppIdentity) -> Self {
+        let raw_args: Vec<String> = std::env::args().collect();
+        let program_name = raw_args
+            .first()
+            .map(|arg0| {
+                std::path::Path::new(arg0)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| arg0.clone())
+            })
+            .unwrap_or_default();
         Self {
             identity,
             parser: ArgParser::new(),
             parsed: ParsedArg::new(),
-            raw_args: std::env::args().peekable(),
+            raw_args: raw_args.clone().into_iter().peekable(),
+            argv: raw_args,
+            preprocessors: Vec::new(),
+            cross_validators: Vec::new(),
+            extensions: Vec::new(),
+            program_name,
+            trace_internals: false,
+            docs_command: false,
+            completion_command: false,
+            config: None,
+            config_path: None,
+            strict_config: false,
+            reserved_config_keys: Vec::new(),
+            config_checked: false,
+            defer_config_check: false,
+            doctor: None,
+            history: None,
+            history_args: None,
+            exit_policy: ExitPolicy::default(),
+            error: None,
+            warnings: Vec::new(),
+            quiet_flag: false,
+            yes_flag: false,
+            verbose_errors: false,
+            version_flag: false,
+            about_flag: false,
+            debug_config_flag: false,
+            multicall: false,
+            color: tui::ColorChoice::default(),
+            theme: tui::Theme::default(),
+            io: None,
+            seed: None,
+            session: None,
+            session_path: None,
         }
     }
 
+    pub fn trace_internals(&mut self, enabled: bool) {
+        self.trace_internals = enabled;
+    }
+
+    pub(crate) fn trace_phase(&self, phase: &str, elapsed: Duration, count: usize) {
+        #[cfg(feature = "log")]
+        if self.trace_internals {
+            crate::log::trace(format_args!(
+                "clark phase={} elapsed_us={} count={}",
+                phase,
+                elapsed.as_micros(),
+                count
+            ));
+        }
+        #[cfg(not(feature = "log"))]
+        let _ = (phase, elapsed, count);
+    }
+
+    pub fn color(&mut self, choice: tui::ColorChoice) {
+        self.color = choice;
+    }
+
+    /* Colors for help, errors and warnings, which are only drawn when color is enabled. */
+    pub fn theme(&mut self, theme: tui::Theme) {
+        self.theme = theme;
+    }
+
+    /* Renders a node for stdout, dropping ANSI codes when color is off and fitting a terminal's width. */
+    pub fn render_out(&self, node: &tui::DomNode) -> String {
+        let is_terminal = self.out_is_terminal();
+        fit_terminal(node, is_terminal).render(self.color.enabled(is_terminal))
+    }
+
+    pub fn render_err(&self, node: &tui::DomNode) -> String {
+        let is_terminal = self.err_is_terminal();
+        fit_terminal(node, is_terminal).render(self.color.enabled(is_terminal))
+    }
+
     pub fn identity(&self) -> &AppIdentity {
         &self.identity
     }
@@ -27,27 +178,281 @@ impl App {
         &self.parsed
     }
 
+    pub fn args_mut(&mut self) -> &mut ParsedArg {
+        &mut self.parsed
+    }
+
+    pub fn parser(&self) -> &ArgParser {
+        &self.parser
+    }
+
+    pub fn program_name(&self) -> &str {
+        &self.program_name
+    }
+
     pub fn add_argument(&mut self, key: &str, arg: Arg) {
+        let begin = Instant::now();
         self.parser.add_argument(key, arg);
+        self.trace_register(begin);
+    }
+
+    fn trace_register(&self, begin: Instant) {
+        let count = self
+            .parser
+            .iter()
+            .last()
+            .map(|tier| tier.len())
+            .unwrap_or(0);
+        self.trace_phase("register", begin.elapsed(), count);
+    }
+
+    pub fn add_preprocessor(&mut self, preprocessor: impl ArgPreprocessor + 'static) {
+        self.preprocessors.push(Box::new(preprocessor));
+    }
+
+    pub(crate) fn inject_arg(&mut self, arg: String) {
+        let remaining = std::iter::once(arg).chain(self.raw_args.clone()).collect();
+        self.replace_remaining(remaining);
+    }
+
+    /* Like inject_arg, but before anything is parsed the token goes after the program name. */
+    pub(crate) fn inject_after_program(&mut self, arg: String) {
+        if !self.parsed.is_empty() {
+            return self.inject_arg(arg);
+        }
+        let mut remaining: Vec<String> = self.raw_args.clone().collect();
+        remaining.insert(remaining.len().min(1), arg);
+        self.replace_remaining(remaining);
+    }
+
+    /* argv index of the next token to parse. */
+    fn consumed(&self) -> usize {
+        self.argv.len() - self.raw_args.len()
+    }
+
+    /* Points an error without a position at the token the parser stopped on. */
+    fn locate_error(&self, err: ParseError) -> ParseError {
+        match (err.index(), self.raw_args.clone().next()) {
+            (None, Some(token)) => err.at(self.consumed(), token),
+            _ => err,
+        }
+    }
+
+    /* argv as shown in error snippets, with the program name and secret values masked. */
+    fn display_argv(&self) -> Vec<String> {
+        let mut argv = vec![self.program_name().to_string()];
+        argv.extend(self.redact_tokens(self.argv.get(1..).unwrap_or_default(), &[]));
+        argv
+    }
+
+    /* Swaps the unparsed tokens while keeping argv in sync for error positions. */
+    fn replace_remaining(&mut self, remaining: Vec<String>) {
+        self.argv.truncate(self.consumed());
+        self.argv.extend(remaining.iter().cloned());
+        self.raw_args = remaining.into_iter().peekable();
+    }
+
+    /* Busybox-style: run as a binary or symlink named after an action, that action is chosen. */
+    pub fn multicall(&mut self) {
+        self.multicall = true;
+    }
+
+    pub fn allow_response_files(&mut self) {
+        self.add_preprocessor(ResponseFileExpander::new());
+    }
+
+    pub fn add_cross_validator(&mut self, validator: impl CrossValidator + 'static) {
+        self.cross_validators.push(Box::new(validator));
+    }
+
+    /*
+      Runs once, in registration order, after everything registered so far has parsed. New tiers
+      and arguments added here are parsed in the same call, so help reflects them too.
+    */
+    pub fn extend_after_parse(
+        &mut self,
+        extend: impl FnOnce(&ParsedArg, &mut ArgParser) + 'static,
+    ) {
+        self.extensions.push(Box::new(extend));
+    }
+
+    fn parse_extended(&mut self) -> Result<ParseTimings, ParseError> {
+        let mut timings = self.parser.incremental_parse_with_io(
+            &mut self.parsed,
+            &mut self.raw_args,
+            self.io.as_ref(),
+        )?;
+        while !self.extensions.is_empty() {
+            let extend = self.extensions.remove(0);
+            extend(&self.parsed, &mut self.parser);
+            let more = self.parser.incremental_parse_with_io(
+                &mut self.parsed,
+                &mut self.raw_args,
+                self.io.as_ref(),
+            )?;
+            timings.parse += more.parse;
+            timings.validate += more.validate;
+            timings.tiers += more.tiers;
+        }
+        Ok(timings)
+    }
+
+    /*
+      Checks that need every tier given so far: unknown config keys, Arg::requires and the cross
+      validators. ActionBuilder runs them once the action tier is parsed, before the handler.
+    */
+    pub(crate) fn check_parsed(&mut self) -> Result<(), ParseError> {
+        self.check_config_keys()?;
+        self.parser.check_requirements(&self.parsed)?;
+        self.run_cross_validators()
+    }
+
+    fn run_cross_validators(&self) -> Result<(), ParseError> {
+        self.cross_validators
+            .iter()
+            .try_for_each(|validator| validator.validate(&self.parsed))
+    }
+
+    fn run_preprocessors(&mut self) -> Result<(), ParseError> {
+        if self.preprocessors.is_empty() {
+            return Ok(());
+        }
+        let mut tokens: Vec<String> = self.raw_args.clone().collect();
+        let program = match self.parsed.is_empty() && !tokens.is_empty() {
+            true => Some(tokens.remove(0)),
+            false => None,
+        };
+        for preprocessor in std::mem::take(&mut self.preprocessors) {
+            tokens = preprocessor.process(tokens)?;
+        }
+        self.replace_remaining(program.into_iter().chain(tokens).collect());
+        Ok(())
+    }
+
+    pub fn ignore_case(&mut self, enabled: bool) {
+        self.parser.ignore_case(enabled);
+    }
+
+    pub fn unify_separators(&mut self, enabled: bool) {
+        self.parser.unify_separators(enabled);
+    }
+
+    pub fn allow_abbreviations(&mut self, enabled: bool) {
+        self.parser.allow_abbreviations(enabled);
+    }
+
+    /* Lets `-5` through as a value or positional when no argument is registered under it. */
+    pub fn allow_negative_numbers(&mut self, enabled: bool) {
+        self.parser.allow_negative_numbers(enabled);
+    }
+
+    /* Asks for required arguments nobody gave, only when stdin is a terminal outside CI. */
+    pub fn prompt_missing(&mut self, enabled: bool) {
+        self.parser.prompt_missing(enabled);
+    }
+
+    pub fn tier_name(&mut self, name: impl Into<String>) {
+        self.parser.tier_name(name);
+    }
+
+    pub fn tier_description(&mut self, description: impl Into<String>) {
+        self.parser.tier_description(description);
+    }
+
+    pub fn rename_argument(&mut self, old: &str, new: &str) {
+        self.parser.add_rename(old, new);
     }
 
     pub fn add_positional_argument(&mut self, arg: Arg) {
+        let begin = Instant::now();
         self.parser.add_positional_argument(arg);
         self.add_help_arguments();
+        self.trace_register(begin);
     }
     pub fn add_help_arguments(&mut self) {
-        self.parser.add_argument(
-            "-h",
-            Arg::new()
-                .help("Show the help message for the application")
-                .as_flag(),
-        );
-        self.parser.add_argument(
-            "--help",
-            Arg::new()
-                .help("Show the help message for the application")
-                .as_flag(),
-        );
+        for key in self.help_keys() {
+            self.parser.add_argument(
+                &key,
+                Arg::new()
+                    .help("Show the help message for the application")
+                    .as_flag(),
+            );
+        }
+    }
+
+    pub fn add_version_argument(&mut self) {
+        self.version_flag = true;
+        for key in self.version_keys() {
+            self.parser.add_argument(
+                &key,
+                Arg::new()
+                    .help("Show the version of the application")
+                    .as_flag()
+                    .optional(),
+            );
+        }
+    }
+
+    /* --about prints the identity with its homepage, repository and contact. */
+    pub fn add_about_argument(&mut self) {
+        self.about_flag = true;
+        if let Some(key) = self.parser.options().prefixes.long_key("about") {
+            self.parser.add_argument(
+                &key,
+                Arg::new()
+                    .help("Show information about the application")
+                    .as_flag()
+                    .optional(),
+            );
+        }
+    }
+
+    fn version_keys(&self) -> Vec<String> {
+        let prefixes = &self.parser.options().prefixes;
+        prefixes
+            .short_key('V')
+            .into_iter()
+            .chain(prefixes.long_key("version"))
+            .collect()
+    }
+
+    fn help_keys(&self) -> Vec<String> {
+        let prefixes = &self.parser.options().prefixes;
+        prefixes
+            .short_key('h')
+            .into_iter()
+            .chain(prefixes.long_key("help"))
+            .collect()
+    }
+
+    /* Checks the registered arguments against the app identity, e.g. overdue deprecations. */
+    pub fn validate_definition(&self) -> Result<(), ParseError> {
+        for (idx, tier) in self.parser.iter().enumerate() {
+            ArgValidator::check_definition(&tier.pos, &self.identity)
+                .map_err(|e| e.key(format!("arg{}", idx)))?;
+            for (key, arg) in tier.params_iter() {
+                ArgValidator::check_definition(arg, &self.identity)
+                    .map_err(|e| e.key(key.clone()))?;
+            }
+            tier.check_aliases()?;
+        }
+        Ok(())
+    }
+
+    /*
+      Marks arguments whose key matches the pattern as secret. Only `*` is special and matches any
+      run of characters, the pattern is not a regex.
+    */
+    pub fn redact_keys(&mut self, pattern: impl Into<String>) {
+        self.parser.redact_keys(pattern);
+    }
+
+    pub fn key_prefixes(&mut self, prefixes: ArgKeyPrefixes) {
+        self.parser.key_prefixes(prefixes);
+    }
+
+    pub fn key_style(&mut self, style: ArgKeyStyle) {
+        self.parser.key_style(style);
     }
 
     pub fn arg_len(&self) -> usize {
@@ -55,7 +460,8 @@ impl App {
     }
 
     pub fn print_help_text(&mut self) {
-        let style = tui::DomStyle::new().fg(tui::RgbColor::bright_green());
+        let begin = Instant::now();
+        let style = tui::DomStyle::new().fg(self.theme.help);
         let mut layout = tui::Layout::new().style(style.clone());
         layout = layout.append_child(paragraph!(
             "{} v{}",
@@ -63,64 +469,414 @@ impl App {
             self.identity.version
         ));
 
-        if !self.identity.description.is_empty() {
-            layout = layout.append_child(paragraph!("{}", &self.identity.description));
+        if !self.identity.full_description().is_empty() {
+            layout = layout.append_child(paragraph!("{}", self.identity.full_description()));
         }
-        if let Some(author) = &self.identity.author {
-            layout = layout.append_child(paragraph!("Written by : {}", author));
+        if let Some(authors) = self.identity.authors_line() {
+            layout = layout.append_child(paragraph!("Written by: {}", authors));
         }
         if let Some(license) = &self.identity.license {
             layout = layout.append_child(paragraph!("{}", license));
         }
+        for (label, v) in self.identity.links() {
+            layout = layout.append_child(paragraph!("{}: {}", label, v));
+        }
 
         layout = layout.append_child(paragraph!(""));
 
         for (idx, tier) in self.parser.iter().enumerate() {
             let mut section = tui::Layout::new().style(style.clone());
-            section = section.append_child(paragraph!("arg{idx}:"));
+            section = section.append_child(paragraph!("{}:", tier.label(idx)));
+            if let Some(description) = tier.description() {
+                section = section.append_child(paragraph!("  {}", description));
+            }
 
             if tier.is_empty() {
                 section = section.append_child(paragraph!("  <no keyword arguments defined>"));
             } else {
-                section = section.append_child(paragraph!("  Keyword Arguments:"));
-                for (key, arg) in tier.params_iter() {
-                    let mut entry = tui::Layout::new().style(style.clone().indent(2));
-                    entry = entry.append_child(paragraph!("{}", key));
-                    if let Some(node) = ArgValidator::help(arg) {
-                        entry = entry.append_child(node);
-                    } else {
-                        entry = entry.append_child(paragraph!("<no-help>"));
+                let mut groups: Vec<Option<&str>> = Vec::new();
+                for (_, arg) in tier.params_iter() {
+                    if !groups.contains(&arg.group()) {
+                        groups.push(arg.group());
+                    }
+                }
+                groups.sort_by_key(|group| group.is_some());
+                for group in groups {
+                    section = section.append_child(tui::VStack(
+                        tui::Layout::new()
+                            .style(style.clone().effect(tui::TextEffect::Bold))
+                            .append_child(paragraph!(
+                                "  {}:",
+                                group.unwrap_or("Keyword Arguments")
+                            )),
+                    ));
+                    for (key, arg) in tier.params_iter().filter(|(_, arg)| arg.group() == group) {
+                        let mut entry = tui::Layout::new().style(style.clone().indent(2));
+                        entry = match arg.aliases() {
+                            [] => entry.append_child(paragraph!("{}", key)),
+                            aliases => {
+                                entry.append_child(paragraph!("{}, {}", key, aliases.join(", ")))
+                            }
+                        };
+                        if let Some(node) = ArgValidator::help(arg) {
+                            entry = entry.append_child(node);
+                        } else {
+                            entry = entry.append_child(paragraph!("<no-help>"));
+                        }
+                        section = section.append_child(tui::VStack(entry));
                     }
-                    section = section.append_child(tui::VStack(entry));
                 }
             }
             layout = layout.append_child(tui::VStack(section));
             layout = layout.append_child(paragraph!(""));
         }
-        println!("{}", &tui::VStack(layout));
+        if !self.identity.examples.is_empty() {
+            let mut section = tui::Layout::new()
+                .style(tui::DomStyle::new().fg(self.theme.accent))
+                .append_child(tui::VStack(
+                    tui::Layout::new()
+                        .style(
+                            tui::DomStyle::new()
+                                .fg(self.theme.accent)
+                                .effect(tui::TextEffect::Bold),
+                        )
+                        .append_child(paragraph!("Examples:")),
+                ));
+            for example in self.identity.examples.iter() {
+                section = section.append_child(paragraph!("  $ {}", example));
+            }
+            layout = layout.append_child(tui::VStack(section));
+            layout = layout.append_child(paragraph!(""));
+        }
+        if let Some(epilog) = &self.identity.epilog {
+            layout = layout.append_child(tui::VStack(
+                tui::Layout::new()
+                    .style(
+                        tui::DomStyle::new()
+                            .fg(self.theme.dimmed)
+                            .effect(tui::TextEffect::Italic),
+                    )
+                    .append_child(paragraph!("{}", epilog)),
+            ));
+        }
+        self.write_out(&format!("{}\n", self.render_out(&tui::VStack(layout))));
+        self.trace_phase("help", begin.elapsed(), self.parser.len());
+    }
+
+    /* One line synopsis: required keys spelled out, optional ones folded into [options]. */
+    pub fn usage(&self) -> String {
+        let mut parts = vec![self.program_name().to_string()];
+        for (idx, tier) in self.parser.iter().enumerate() {
+            if idx > 0 {
+                parts.push(format!("<{}>", tier.label(idx)));
+            }
+            let mut has_optional = false;
+            for (key, arg) in tier.params_iter() {
+                let required = ArgValidator::count_range(arg).is_some_and(|(min, _)| min > 0)
+                    && ArgValidator::default_value(arg).is_none()
+                    && ArgValidator::env_var(arg).is_none();
+                match (required, ArgValidator::allows_empty(arg)) {
+                    (false, _) => has_optional = true,
+                    (true, Some(true)) => parts.push(key.to_string()),
+                    (true, _) => parts.push(format!("{} <value>", key)),
+                }
+            }
+            if has_optional {
+                parts.push(String::from("[options]"));
+            }
+        }
+        parts.join(" ")
+    }
+
+    fn help_hint(&self) -> Option<String> {
+        let registered = self.parser.iter().next()?;
+        self.help_keys()
+            .into_iter()
+            .rfind(|key| registered.params_iter().any(|(k, _)| key.as_str() == k))
+            .map(|key| {
+                format!(
+                    "Run '{} {}' for more information.",
+                    self.program_name(),
+                    key
+                )
+            })
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub(crate) fn add_warning(&mut self, msg: impl Into<String>) {
+        self.parsed.add_warning(msg);
+    }
+
+    pub fn add_quiet_flag(&mut self) {
+        self.quiet_flag = true;
+        let prefixes = &self.parser.options().prefixes;
+        let keys: Vec<String> = prefixes
+            .short_key('q')
+            .into_iter()
+            .chain(prefixes.long_key("quiet"))
+            .collect();
+        for key in keys {
+            self.add_argument(
+                &key,
+                Arg::new()
+                    .help("Suppress warnings printed after parsing")
+                    .as_flag()
+                    .optional(),
+            );
+        }
+    }
+
+    fn is_quiet(&self) -> bool {
+        let prefixes = &self.parser.options().prefixes;
+        self.quiet_flag
+            && prefixes
+                .short_key('q')
+                .into_iter()
+                .chain(prefixes.long_key("quiet"))
+                .any(|key| self.parsed.all_param_iter().any(|(k, _)| key.as_str() == k))
+    }
+
+    /*
+      Only warnings not rendered before are, so re-validating a tier on the next parse stays
+      quiet. All of them stay in warnings().
+    */
+    pub(crate) fn print_warnings(&mut self) {
+        let warnings: Vec<String> = self
+            .parsed
+            .take_warnings()
+            .into_iter()
+            .filter(|warning| !self.warnings.contains(warning))
+            .collect();
+        self.warnings.extend(warnings.iter().cloned());
+        if warnings.is_empty() || self.is_quiet() {
+            return;
+        }
+        let style = tui::DomStyle::new().fg(self.theme.warning);
+        let mut layout = tui::Layout::default()
+            .style(style.clone())
+            .append_child(tui::VStack(
+                tui::Layout::default()
+                    .style(style.effect(tui::TextEffect::Bold))
+                    .append_child(paragraph!(
+                        "{} warning{}:",
+                        warnings.len(),
+                        if warnings.len() == 1 { "" } else { "s" }
+                    )),
+            ));
+        for warning in &warnings {
+            layout = layout.append_child(paragraph!("  - {}", warning));
+        }
+        self.write_err(&self.render_err(&tui::VStack(layout)));
+    }
+
+    /* Prints the source() chain of errors below the message. */
+    pub fn verbose_errors(&mut self, enabled: bool) {
+        self.verbose_errors = enabled;
+    }
+
+    pub fn on_error(&mut self, policy: ExitPolicy) {
+        self.exit_policy = policy;
+    }
+
+    pub fn error(&self) -> Option<&ParseError> {
+        self.error.as_ref()
+    }
+
+    /* With injected io the error is rendered to the sink and kept, as if the policy were Return. */
+    pub(crate) fn handle_error(&mut self, err: ParseError) {
+        let code = match self.exit_policy {
+            ExitPolicy::Return if !self.has_io() => {
+                self.error = Some(err);
+                return;
+            }
+            ExitPolicy::ExitWith(code) => code,
+            ExitPolicy::ExitWithKind => err.kind.exit_code(),
+            ExitPolicy::Return => 0,
+        };
+        let mut layout = tui::Layout::default()
+            .append_child(paragraph!("{}", err))
+            .style(tui::DomStyle::new().fg(self.theme.error));
+        if self.verbose_errors {
+            for cause in err.causes() {
+                layout = layout.append_child(paragraph!("  caused by: {}", cause));
+            }
+        }
+        for suggestion in err.suggestions() {
+            layout = layout.append_child(paragraph!("  did you mean {}?", suggestion));
+        }
+        if let Some(snippet) = err.snippet(&self.display_argv()) {
+            layout = layout.append_child(tui::VStack(
+                tui::Layout::new()
+                    .style(tui::DomStyle::new().indent(2))
+                    .append_child(paragraph!("{}", snippet)),
+            ));
+        }
+        let mut hint = tui::Layout::new()
+            .style(tui::DomStyle::new().effect(tui::TextEffect::Dim))
+            .append_child(paragraph!("Usage: {}", self.usage()));
+        if let Some(line) = self.help_hint() {
+            hint = hint.append_child(paragraph!("{}", line));
+        }
+        layout = layout.append_child(tui::VStack(hint));
+        self.write_err(&format!("{}\n", self.render_err(&tui::VStack(layout))));
+        if self.has_io() {
+            self.error = Some(err);
+            return;
+        }
+        self.exit(code);
     }
 
     pub fn parse_args(&mut self, auto_help: bool) -> &ParsedArg {
-        let res = self
-            .parser
-            .incremental_parse(&mut self.parsed, &mut self.raw_args);
-        if auto_help && (self.parsed.count("-h") + self.parsed.count("--help") > 0) {
-            self.print_help_text();
-            std::process::exit(0);
-        }
-        match res {
-            Ok(_) => &self.parsed,
-            Err(err) => {
-                eprintln!(
-                    "{}",
-                    tui::VStack(
-                        tui::Layout::default()
-                            .append_child(paragraph!("{}", err))
-                            .style(tui::DomStyle::new().fg(tui::RgbColor::bright_yellow())),
-                    )
-                );
-                std::process::exit(1);
+        self.error = None;
+        match self.try_parse_args(auto_help) {
+            Ok(outcome) => {
+                self.render_outcome(outcome);
             }
+            Err(err) => self.handle_error(err),
         }
+        &self.parsed
+    }
+
+    /*
+      Like parse_args but hands the error back instead of applying the exit policy, and reports
+      help or version requests as a ParseOutcome instead of printing them.
+    */
+    pub fn try_parse_args(&mut self, auto_help: bool) -> Result<ParseOutcome, ParseError> {
+        if self.parsed.is_empty() {
+            self.capture_history();
+        }
+        /* None when a built-in command already wrote its output. */
+        let res = self.run_preprocessors().and_then(|()| {
+            if self.docs_command && self.is_builtin_command("docs") {
+                self.write_out(&self.docs_markdown());
+                return Ok(None);
+            }
+            if self.completion_command && self.is_builtin_command("completion") {
+                let shell = self.raw_args.clone().nth(2).unwrap_or_default();
+                let shell = Shell::try_from(shell.as_str()).map_err(|e| e.key("completion"))?;
+                self.write_out(&self.completion_script(shell));
+                return Ok(None);
+            }
+            if self.history.is_some() && self.is_builtin_command("history") {
+                self.print_history()?;
+                return Ok(None);
+            }
+            if self.doctor.is_some() && self.is_builtin_command("doctor") {
+                self.run_doctor()?;
+                return Ok(None);
+            }
+            if self.parsed.is_empty() && self.apply_config()? {
+                return Ok(None);
+            }
+            let timings = self.parse_extended()?;
+            if !self.defer_config_check && self.raw_args.peek().is_none() {
+                self.check_parsed()?;
+            }
+            Ok(Some(timings))
+        });
+        let res = res.map_err(|e| self.locate_error(e));
+        match &res {
+            Ok(Some(timings)) => {
+                self.trace_phase("parse", timings.parse, timings.tiers);
+                self.trace_phase("validate", timings.validate, timings.tiers);
+            }
+            Ok(None) => return Ok(ParseOutcome::Handled),
+            Err(_) => self.history_args = None,
+        }
+        self.print_warnings();
+        /* A help request wins over a parse error, e.g. a missing required argument. */
+        match self.requested_outcome() {
+            outcome if auto_help && outcome != ParseOutcome::Parsed => Ok(outcome),
+            _ => res.map(|_| ParseOutcome::Parsed),
+        }
+    }
+
+    /*
+      Whether the first token asks for a built-in command. A positional registered for that spot
+      which accepts the name, like an action of the same name, keeps it instead.
+    */
+    fn is_builtin_command(&self, name: &str) -> bool {
+        self.parsed.is_empty()
+            && self.raw_args.clone().nth(1).as_deref() == Some(name)
+            && self
+                .parser
+                .iter()
+                .nth(1)
+                .is_none_or(|tier| ArgValidator::check_value(&tier.pos, Some(name)).is_err())
+    }
+
+    /* Inspects what has been parsed so far for help, --help-json or version flags. */
+    pub fn requested_outcome(&self) -> ParseOutcome {
+        let given = |keys: Vec<String>| {
+            keys.iter()
+                .any(|key| self.parsed.all_param_iter().any(|(k, _)| key.as_str() == k))
+        };
+        if self.help_json_requested() {
+            ParseOutcome::HelpJsonRequested
+        } else if !self.parsed.is_empty()
+            && self
+                .help_keys()
+                .iter()
+                .any(|key| self.parsed.contains(key.as_str()))
+        {
+            ParseOutcome::HelpRequested(self.parsed.len() - 1)
+        } else if self.version_flag && given(self.version_keys()) {
+            ParseOutcome::VersionRequested
+        } else if self.about_flag
+            && given(
+                self.parser
+                    .options()
+                    .prefixes
+                    .long_key("about")
+                    .into_iter()
+                    .collect(),
+            )
+        {
+            ParseOutcome::AboutRequested
+        } else if self.debug_config_flag && given(self.debug_config_keys()) {
+            ParseOutcome::DebugConfigRequested
+        } else {
+            ParseOutcome::Parsed
+        }
+    }
+
+    /* Prints what the outcome asks for, returns whether anything was printed under ExitPolicy::Return. */
+    pub(crate) fn render_outcome(&mut self, outcome: ParseOutcome) -> bool {
+        if !self.print_outcome(outcome) {
+            return false;
+        }
+        if self.exit_policy != ExitPolicy::Return && !self.has_io() {
+            output::exit(0);
+        }
+        true
+    }
+
+    /* Like render_outcome but never exits, for callers that hand an exit code back. */
+    pub(crate) fn print_outcome(&mut self, outcome: ParseOutcome) -> bool {
+        match outcome {
+            ParseOutcome::Parsed => return false,
+            ParseOutcome::HelpRequested(_) => self.print_help_text(),
+            ParseOutcome::HelpJsonRequested => self.write_out(&format!("{}\n", self.spec_json())),
+            ParseOutcome::VersionRequested => self.write_out(&format!(
+                "{} v{}\n",
+                self.identity.name, self.identity.version
+            )),
+            ParseOutcome::AboutRequested => self.write_out(&self.identity.to_string()),
+            ParseOutcome::DebugConfigRequested => {
+                self.write_out(&self.render_out(&self.effective_settings()))
+            }
+            ParseOutcome::Handled => {}
+        }
+        true
+    }
+}
+
+fn fit_terminal(node: &tui::DomNode, is_terminal: bool) -> tui::DomNode {
+    match tui::terminal_width().filter(|_| is_terminal) {
+        Some(width) => tui::VStack(tui::Layout::new().width(width).append_child(node.clone())),
+        None => node.clone(),
     }
 }
@@ -0,0 +1,235 @@
+use std::{fmt::Write, path::PathBuf};
+
+use crate::{
+    App, Arg, ArgKey, ConfigFile, ParseError, ValueLayer, ValueSource, config::config_key,
+    paragraph, suggest, tui,
+};
+
+impl App {
+    pub fn config(&mut self, config: ConfigFile) {
+        self.config = Some(config);
+        self.add_profile_argument();
+    }
+
+    /* The file is read on the first parse, a missing file is treated as an empty config. */
+    pub fn config_file(&mut self, path: impl Into<PathBuf>) {
+        self.config_path = Some(path.into());
+        self.add_profile_argument();
+    }
+
+    pub fn strict_config(&mut self, enabled: bool) {
+        self.strict_config = enabled;
+    }
+
+    pub fn config_ref(&self) -> Option<&ConfigFile> {
+        self.config.as_ref()
+    }
+
+    fn profile_key(&self) -> Option<String> {
+        self.parser().options().prefixes.long_key("profile")
+    }
+
+    fn add_profile_argument(&mut self) {
+        if let Some(key) = self.profile_key() {
+            self.add_argument(
+                &key,
+                Arg::new()
+                    .help("Load default values from a named profile in the config file")
+                    .require_value()
+                    .optional(),
+            );
+        }
+    }
+
+    fn selected_profile(&self) -> Option<String> {
+        let options = self.parser().options();
+        let profile_key = ArgKey::make_with(&self.profile_key()?, &options.prefixes).ok()?;
+        let mut tokens = self.raw_args.clone().skip(1);
+        while let Some(token) = tokens.next() {
+            if let Ok((key, value)) = ArgKey::parse_arg_with(&token, &options.prefixes)
+                && options.key_matches(&profile_key, &key)
+            {
+                return value.map(String::from).or_else(|| tokens.next());
+            }
+        }
+        None
+    }
+
+    /* Returns true when `profiles list` was handled and nothing is left to parse. */
+    pub(crate) fn apply_config(&mut self) -> Result<bool, ParseError> {
+        if self.config.is_none()
+            && let Some(path) = &self.config_path
+            && path.exists()
+        {
+            self.config = Some(ConfigFile::load(path)?);
+        }
+        if self.config.is_none() && self.config_path.is_none() {
+            return Ok(false);
+        }
+        let config = self.config.take().unwrap_or_default();
+        let mut tokens = self.raw_args.clone().skip(1);
+        if tokens.next().as_deref() == Some("profiles") && tokens.next().as_deref() == Some("list")
+        {
+            let mut buf = String::new();
+            for profile in config.profiles_iter() {
+                let _ = writeln!(buf, "{}", profile.name);
+                for (k, v) in profile.values.iter() {
+                    let _ = match v.is_empty() {
+                        true => writeln!(buf, "  {}", k),
+                        false => writeln!(buf, "  {} = {}", k, v),
+                    };
+                }
+            }
+            self.write_out(&buf);
+            self.config = Some(config);
+            return Ok(true);
+        }
+        let prefixes = self.parser().options().prefixes.clone();
+        if let Some(name) = self.selected_profile() {
+            match config.profile(&name) {
+                Some(profile) => self.parser.add_value_layer(ValueLayer::from_pairs(
+                    ValueSource::Profile,
+                    &profile.values,
+                    &prefixes,
+                )),
+                None => {
+                    return Err(ParseError::invalid_value(format_args!(
+                        "unknown profile {}",
+                        name
+                    ))
+                    .key(self.profile_key().unwrap_or_default()));
+                }
+            }
+        }
+        self.parser.add_value_layer(ValueLayer::from_pairs(
+            ValueSource::Config,
+            &config.values,
+            &prefixes,
+        ));
+        self.config = Some(config);
+        Ok(false)
+    }
+
+    pub(crate) fn check_config_keys(&mut self) -> Result<(), ParseError> {
+        if self.config_checked {
+            return Ok(());
+        }
+        self.config_checked = true;
+        for (k, suggestion) in self.unknown_config_keys() {
+            if self.strict_config {
+                let err = ParseError::not_argument_key(format_args!("unknown config key {}", k));
+                return Err(match suggestion {
+                    Some(s) => err.suggest(s),
+                    None => err,
+                });
+            }
+            let hint = suggestion
+                .map(|s| format!(", did you mean {}?", s))
+                .unwrap_or_default();
+            self.add_warning(format!(
+                "config key {} does not match any argument and is ignored{}",
+                k, hint
+            ));
+        }
+        Ok(())
+    }
+
+    fn unknown_config_keys(&self) -> Vec<(String, Option<String>)> {
+        let Some(config) = &self.config else {
+            return Vec::new();
+        };
+        let options = self.parser().options();
+        let registered: Vec<&ArgKey> = self
+            .parser()
+            .iter()
+            .flat_map(|tier| tier.params_iter().map(|(k, _)| k))
+            .collect();
+        config
+            .values
+            .iter()
+            .chain(config.profiles_iter().flat_map(|p| p.values.iter()))
+            .filter(|(k, _)| !self.reserved_config_keys.contains(k))
+            .filter(|(k, _)| {
+                !config_key(k, &options.prefixes).is_some_and(|key| {
+                    registered
+                        .iter()
+                        .any(|registered| options.key_matches(registered, &key))
+                })
+            })
+            .map(|(k, _)| {
+                let suggestion = suggest::closest(
+                    k.trim_start_matches(['-']),
+                    registered
+                        .iter()
+                        .map(|key| key.value.trim_start_matches(['-'])),
+                );
+                (k.clone(), suggestion.map(String::from))
+            })
+            .collect()
+    }
+
+    pub fn config_value(&self, key: &str) -> Option<String> {
+        let options = self.parser().options();
+        let key = config_key(key, &options.prefixes)?;
+        self.parser().value_layers().iter().find_map(|layer| {
+            layer
+                .iter()
+                .find(|(k, _)| options.key_matches(k, &key))
+                .map(|(_, v)| v.clone())
+        })
+    }
+
+    /* --debug-config prints every effective value and where it came from instead of running. */
+    pub fn add_debug_config_argument(&mut self) {
+        self.debug_config_flag = true;
+        for key in self.debug_config_keys() {
+            self.add_argument(
+                key.as_str(),
+                Arg::new()
+                    .help("Show the effective settings and their sources, then exit")
+                    .as_flag()
+                    .optional(),
+            );
+        }
+    }
+
+    pub(crate) fn debug_config_keys(&self) -> Vec<String> {
+        self.parser()
+            .options()
+            .prefixes
+            .long_key("debug-config")
+            .into_iter()
+            .collect()
+    }
+
+    pub fn effective_settings(&self) -> tui::DomNode {
+        let skip = self.debug_config_keys();
+        let mut layout = tui::Layout::new();
+        let mut current = None;
+        for (tier, key, value, source) in self.args().provenance() {
+            if skip.iter().any(|k| k.as_str() == key) {
+                continue;
+            }
+            if current != Some(tier) {
+                current = Some(tier);
+                layout = layout.append_child(tui::VStack(
+                    tui::Layout::new()
+                        .style(tui::DomStyle::new().effect(tui::TextEffect::Bold))
+                        .append_child(paragraph!(
+                            "{}:",
+                            self.args().arg_at(tier).unwrap_or_default()
+                        )),
+                ));
+            }
+            let value = self
+                .parser()
+                .iter()
+                .flat_map(|tier| tier.params_iter())
+                .find(|(k, _)| k == key)
+                .map(|(_, arg)| arg.redact(value))
+                .unwrap_or(value);
+            layout = layout.append_child(paragraph!("  {} = {} ({})", key, value, source));
+        }
+        tui::VStack(layout)
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::Write;
+
+use crate::{App, Arg, ArgValidator, ExitPolicy, ParseErrorKind, tui};
+
+impl App {
+    pub fn add_docs_command(&mut self) {
+        self.docs_command = true;
+    }
+
+    pub fn docs_markdown(&self) -> String {
+        let program = self.program_name();
+        let identity = self.identity();
+        let mut buf = String::new();
+
+        let _ = writeln!(buf, "# {}\n", identity.name);
+        if !identity.full_description().is_empty() {
+            let _ = writeln!(buf, "{}\n", identity.full_description());
+        }
+
+        let _ = writeln!(buf, "## Installation\n");
+        let _ = writeln!(buf, "```bash\ncargo install {}\n```\n", program);
+
+        let _ = writeln!(buf, "## Usage\n");
+        let mut usage = String::from(program);
+        for (idx, tier) in self.parser().iter().enumerate() {
+            if idx > 0 {
+                let _ = write!(usage, " <{}>", tier.label(idx));
+            }
+            if !tier.is_empty() {
+                usage.push_str(" [OPTIONS]");
+            }
+        }
+        let _ = writeln!(buf, "```bash\n{}\n```\n", usage);
+
+        let _ = writeln!(buf, "## Flags\n");
+        for (idx, tier) in self.parser().iter().enumerate() {
+            if self.parser().len() > 1 {
+                let _ = writeln!(buf, "### {}\n", tier.label(idx));
+            }
+            if let Some(description) = tier.description() {
+                let _ = writeln!(buf, "{}\n", description);
+            }
+            if idx > 0 {
+                let _ = writeln!(buf, "{}\n", markdown_help(&tier.pos));
+            }
+            let _ = writeln!(buf, "| Flag | Description |");
+            let _ = writeln!(buf, "|------|-------------|");
+            for (key, arg) in tier.params_iter() {
+                let keys = std::iter::once(key.to_string())
+                    .chain(arg.aliases().iter().cloned())
+                    .map(|k| format!("`{}`", k))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let _ = writeln!(buf, "| {} | {} |", keys, markdown_help(arg));
+            }
+            let _ = writeln!(buf);
+        }
+
+        let _ = writeln!(buf, "## Examples\n");
+        let _ = writeln!(buf, "```bash\n{} --help", program);
+        for example in identity.examples.iter() {
+            let _ = writeln!(buf, "{}", example);
+        }
+        let _ = writeln!(buf, "```\n");
+
+        let _ = writeln!(buf, "## Exit Codes\n");
+        let _ = writeln!(buf, "| Code | Meaning |");
+        let _ = writeln!(buf, "|------|---------|");
+        for (code, meaning) in self.exit_codes() {
+            let _ = writeln!(buf, "| {} | {} |", code, meaning);
+        }
+        if let Some(epilog) = &identity.epilog {
+            let _ = writeln!(buf, "\n{}", epilog);
+        }
+        buf
+    }
+
+    /* What each exit code means under the app's ExitPolicy, kinds sharing a code are listed together. */
+    fn exit_codes(&self) -> Vec<(i32, String)> {
+        let mut codes = vec![(0, String::from("Success"))];
+        match self.exit_policy {
+            ExitPolicy::ExitWith(code) => codes.push((code, String::from("Invalid arguments"))),
+            ExitPolicy::ExitWithKind => {
+                for kind in ParseErrorKind::ALL {
+                    match codes.iter_mut().find(|(code, _)| *code == kind.exit_code()) {
+                        Some((_, meaning)) => {
+                            meaning.push_str(", ");
+                            meaning.push_str(kind.describe());
+                        }
+                        None => codes.push((kind.exit_code(), capitalize(kind.describe()))),
+                    }
+                }
+                codes.sort_by_key(|(code, _)| *code);
+            }
+            ExitPolicy::Return => {}
+        }
+        codes
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn markdown_help(arg: &Arg) -> String {
+    ArgValidator::help(arg)
+        .map(|node| {
+            tui::strip_ansi(&node.to_string())
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("; ")
+                .replace('|', "\\|")
+        })
+        .unwrap_or_default()
+}
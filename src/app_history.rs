@@ -0,0 +1,210 @@
+use crate::{
+    App, ArgKey, HistoryEntry, HistoryFile, ParseError,
+    arg_parser::wildcard_match,
+    lexer::{Lexer, Token},
+    output, paragraph, tui,
+};
+
+impl App {
+    pub fn history(&mut self, history: HistoryFile) {
+        self.history = Some(history);
+    }
+
+    pub fn history_file(&self) -> Option<&HistoryFile> {
+        self.history.as_ref()
+    }
+
+    /* Records the invocation with the given exit code before exiting. */
+    pub fn exit(&mut self, code: i32) -> ! {
+        self.record_history(code);
+        self.save_session();
+        output::exit(code);
+    }
+
+    pub(crate) fn capture_history(&mut self) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        let extra: Vec<&str> = history.redacted_iter().map(String::as_str).collect();
+        let tokens: Vec<String> = self.raw_args.clone().skip(1).collect();
+        let mut args = vec![self.program_name().to_string()];
+        args.extend(self.redact_tokens(&tokens, &extra));
+        self.history_args = Some(args);
+    }
+
+    /*
+      Masks values of secret arguments and of keys matching the extra `*` wildcard patterns, one
+      output token per input token. Tokens are split like the parser does, so `--key=v`, `-k=v`
+      and `-kv` keep their key and lose the value.
+    */
+    pub(crate) fn redact_tokens(&self, tokens: &[String], extra: &[&str]) -> Vec<String> {
+        let options = self.parser.options();
+        let is_secret = |key: &ArgKey| {
+            extra.iter().any(|p| wildcard_match(p, key.as_str()))
+                || self.parser.iter().any(|tier| {
+                    matches!(tier.find_param(key, options), Ok(Some((_, arg))) if arg.is_secret())
+                })
+        };
+        let lexed = Lexer::new(options.prefixes.clone()).tokens(tokens);
+        let mut args = Vec::with_capacity(tokens.len());
+        let mut mask_next = false;
+        for (token, raw) in lexed.iter().zip(tokens) {
+            if std::mem::take(&mut mask_next) {
+                args.push(String::from("***"));
+                continue;
+            }
+            let key = match token {
+                Token::ShortCluster {
+                    prefix, cluster, ..
+                } => cluster
+                    .chars()
+                    .next()
+                    .map(|c| ArgKey::make_unchecked(&format!("{}{}", prefix, c))),
+                _ => token.key(),
+            };
+            let Some(key) = key.filter(|k| is_secret(k)) else {
+                args.push(raw.clone());
+                continue;
+            };
+            match token {
+                Token::LongFlag { value: Some(_), .. } => {
+                    args.push(format!("{}=***", ArgKey::split_value(raw).0))
+                }
+                Token::ShortCluster { value: Some(_), .. } => args.push(format!("{}=***", key)),
+                Token::ShortCluster { cluster, .. } if cluster.chars().count() > 1 => {
+                    args.push(format!("{}***", key))
+                }
+                _ => {
+                    args.push(raw.clone());
+                    mask_next = true;
+                }
+            }
+        }
+        args
+    }
+
+    pub(crate) fn record_history(&mut self, exit_code: i32) {
+        if let Some(history) = &self.history
+            && let Some(args) = self.history_args.take()
+            && let Err(e) = history.append(&HistoryEntry::new(args, exit_code))
+        {
+            self.write_err(&format!("{}\n", e));
+        }
+    }
+
+    pub(crate) fn print_history(&self) -> Result<(), ParseError> {
+        let Some(history) = &self.history else {
+            return Ok(());
+        };
+        let entries = history.entries()?;
+        let rows: Vec<[String; 4]> = entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| {
+                [
+                    (id + 1).to_string(),
+                    entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    entry.exit_code.to_string(),
+                    entry.command_line(),
+                ]
+            })
+            .collect();
+        let header = [
+            String::from("#"),
+            String::from("TIME"),
+            String::from("EXIT"),
+            String::from("COMMAND"),
+        ];
+        let widths: Vec<usize> = (0..header.len())
+            .map(|col| {
+                rows.iter()
+                    .chain(std::iter::once(&header))
+                    .map(|row| row[col].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let render = |row: &[String; 4]| {
+            format!(
+                "{:<w0$}  {:<w1$}  {:<w2$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2]
+            )
+        };
+        let mut layout = tui::Layout::new().append_child(tui::VStack(
+            tui::Layout::new()
+                .style(tui::DomStyle::new().effect(tui::TextEffect::Bold))
+                .append_child(paragraph!("{}", render(&header))),
+        ));
+        for row in rows.iter() {
+            layout = layout.append_child(paragraph!("{}", render(row)));
+        }
+        self.write_out(&self.render_out(&tui::VStack(layout)));
+        Ok(())
+    }
+}
+
+impl Drop for App {
+    fn drop(&mut self) {
+        self.record_history(0);
+        self.save_session();
+        output::flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{App, AppIdentity, AppVersion, Arg, ArgEmptyValidator};
+
+    fn redact(tokens: &[&str], extra: &[&str]) -> Vec<String> {
+        let mut app = App::new(AppIdentity::new("test", "", AppVersion::new(1, 0, 0)));
+        app.add_argument(
+            "--token",
+            Arg::new()
+                .validate(ArgEmptyValidator::require_value())
+                .alias("-t")
+                .attach_value()
+                .secret(),
+        );
+        app.add_argument(
+            "-v",
+            Arg::new().validate(ArgEmptyValidator::require_value()),
+        );
+        let tokens: Vec<String> = tokens.iter().map(|t| t.to_string()).collect();
+        app.redact_tokens(&tokens, extra)
+    }
+
+    #[test]
+    fn masks_long_forms() {
+        assert_eq!(redact(&["--token", "abc"], &[]), ["--token", "***"]);
+        assert_eq!(redact(&["--token=abc"], &[]), ["--token=***"]);
+    }
+
+    #[test]
+    fn masks_attached_short_forms() {
+        assert_eq!(redact(&["-tabc"], &[]), ["-t***"]);
+        assert_eq!(redact(&["-t=abc"], &[]), ["-t=***"]);
+        assert_eq!(redact(&["-t", "abc"], &[]), ["-t", "***"]);
+    }
+
+    #[test]
+    fn keeps_other_tokens() {
+        assert_eq!(redact(&["-v", "abc", "-vabc"], &[]), ["-v", "abc", "-vabc"]);
+        assert_eq!(
+            redact(&["--", "--token", "abc"], &[]),
+            ["--", "--token", "abc"]
+        );
+    }
+
+    #[test]
+    fn masks_wildcard_patterns() {
+        assert_eq!(redact(&["-v", "abc"], &["-*"]), ["-v", "***"]);
+        assert_eq!(redact(&["-vabc"], &["v"]), ["-v***"]);
+        assert_eq!(redact(&["-v", "abc"], &["--v.*"]), ["-v", "abc"]);
+    }
+}
@@ -6,9 +6,15 @@ use crate::AppVersion;
 pub struct AppIdentity {
     pub name: String,
     pub description: String,
-    pub author: Option<String>,
+    pub long_description: Option<String>,
+    pub authors: Vec<String>,
     pub license: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub contact: Option<String>,
     pub version: AppVersion,
+    pub examples: Vec<String>,
+    pub epilog: Option<String>,
 }
 
 impl AppIdentity {
@@ -20,21 +26,118 @@ impl AppIdentity {
         Self {
             name: name.into(),
             description: description.into(),
-            author: None,
+            long_description: None,
+            authors: Vec::new(),
             license: None,
+            homepage: None,
+            repository: None,
+            contact: None,
             version,
+            examples: Vec::new(),
+            epilog: None,
         }
     }
 
+    /* Builds the identity from Cargo package metadata, see from_cargo!. */
+    pub fn from_package(
+        name: &str,
+        description: &str,
+        version: &str,
+        authors: &str,
+        license: &str,
+        homepage: &str,
+        repository: &str,
+    ) -> Self {
+        let mut identity = Self::new(
+            name,
+            description,
+            AppVersion::try_from(version).unwrap_or_default(),
+        );
+        identity = identity.authors(authors.split(':').map(str::trim).filter(|a| !a.is_empty()));
+        if !license.is_empty() {
+            identity = identity.license(license);
+        }
+        if !homepage.is_empty() {
+            identity = identity.homepage(homepage);
+        }
+        if !repository.is_empty() {
+            identity = identity.repository(repository);
+        }
+        identity
+    }
+
     pub fn author(mut self, author: impl Into<String>) -> Self {
-        self.author = Some(author.into());
+        self.authors.push(author.into());
         self
     }
 
+    pub fn authors<I: IntoIterator<Item = S>, S: Into<String>>(mut self, authors: I) -> Self {
+        self.authors.extend(authors.into_iter().map(Into::into));
+        self
+    }
+
+    /* "a, b" for the "Written by" line, None without authors. */
+    pub fn authors_line(&self) -> Option<String> {
+        match self.authors.is_empty() {
+            true => None,
+            false => Some(self.authors.join(", ")),
+        }
+    }
+
     pub fn license(mut self, license: impl Into<String>) -> Self {
         self.license = Some(license.into());
         self
     }
+
+    /* Shown instead of description in the full help and docs only. */
+    pub fn long_description(mut self, text: impl Into<String>) -> Self {
+        self.long_description = Some(text.into());
+        self
+    }
+
+    pub fn full_description(&self) -> &str {
+        self.long_description
+            .as_deref()
+            .unwrap_or(&self.description)
+    }
+
+    pub fn homepage(mut self, homepage: impl Into<String>) -> Self {
+        self.homepage = Some(homepage.into());
+        self
+    }
+
+    pub fn repository(mut self, repository: impl Into<String>) -> Self {
+        self.repository = Some(repository.into());
+        self
+    }
+
+    /* Where users ask for support, an email address or an URL. */
+    pub fn contact(mut self, contact: impl Into<String>) -> Self {
+        self.contact = Some(contact.into());
+        self
+    }
+
+    /* Labelled homepage, repository and contact lines, in that order. */
+    pub fn links(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Homepage", &self.homepage),
+            ("Repository", &self.repository),
+            ("Contact", &self.contact),
+        ]
+        .into_iter()
+        .filter_map(|(label, v)| v.as_deref().map(|v| (label, v)))
+        .collect()
+    }
+
+    pub fn example(mut self, example: impl Into<String>) -> Self {
+        self.examples.push(example.into());
+        self
+    }
+
+    pub fn epilog(mut self, epilog: impl Into<String>) -> Self {
+        self.epilog = Some(epilog.into());
+        self
+    }
 }
 
 impl fmt::Display for AppIdentity {
@@ -43,12 +146,31 @@ impl fmt::Display for AppIdentity {
         if !self.description.is_empty() {
             writeln!(f, "{}", self.description)?;
         }
-        if let Some(author) = &self.author {
-            writeln!(f, "Written by : {}", author)?;
+        if let Some(authors) = self.authors_line() {
+            writeln!(f, "Written by: {}", authors)?;
         }
         if let Some(license) = &self.license {
             writeln!(f, "{}", license)?;
         }
+        for (label, v) in self.links() {
+            writeln!(f, "{}: {}", label, v)?;
+        }
         Ok(())
     }
 }
+
+/* Reads the identity from the calling crate's Cargo.toml so it never goes stale. */
+#[macro_export]
+macro_rules! from_cargo {
+    () => {
+        $crate::AppIdentity::from_package(
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_DESCRIPTION"),
+            env!("CARGO_PKG_VERSION"),
+            env!("CARGO_PKG_AUTHORS"),
+            env!("CARGO_PKG_LICENSE"),
+            env!("CARGO_PKG_HOMEPAGE"),
+            env!("CARGO_PKG_REPOSITORY"),
+        )
+    };
+}
@@ -0,0 +1,136 @@
+use std::{
+    cell::RefCell,
+    io::{self, BufRead, IsTerminal, Write},
+    sync::{Arc, Mutex},
+};
+
+use crate::{App, ParseError, Prompt, output};
+
+/* Injected sinks, when present nothing is written to stdout/stderr and the process never exits. */
+pub(crate) struct AppIo {
+    output: Box<dyn Write>,
+    input: Box<dyn BufRead>,
+}
+
+impl AppIo {
+    pub(crate) fn split(&mut self) -> (&mut dyn BufRead, &mut dyn Write) {
+        (&mut self.input, &mut self.output)
+    }
+}
+
+/* A cloneable in-memory writer, hand one clone to App::with_io and inspect the other. */
+#[derive(Debug, Clone, Default)]
+pub struct OutputCapture {
+    buf: Arc<Mutex<Vec<u8>>>,
+}
+
+impl OutputCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buf.lock().unwrap()).into_owned()
+    }
+
+    pub fn clear(&self) {
+        self.buf.lock().unwrap().clear();
+    }
+
+    #[track_caller]
+    pub fn assert_contains(&self, needle: &str) {
+        let contents = self.contents();
+        assert!(
+            contents.contains(needle),
+            "expected output to contain {:?}, got:\n{}",
+            needle,
+            contents
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_not_contains(&self, needle: &str) {
+        let contents = self.contents();
+        assert!(
+            !contents.contains(needle),
+            "expected output not to contain {:?}, got:\n{}",
+            needle,
+            contents
+        );
+    }
+}
+
+impl Write for OutputCapture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl App {
+    /* Routes help, warnings, errors and prompts through the given sinks, for in-process tests. */
+    pub fn with_io(&mut self, writer: impl Write + 'static, reader: impl BufRead + 'static) {
+        self.io = Some(RefCell::new(AppIo {
+            output: Box::new(writer),
+            input: Box::new(reader),
+        }));
+    }
+
+    /* Replaces the process arguments, the program name is kept. */
+    pub fn with_args<I, S>(&mut self, args: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.argv = std::iter::once(self.program_name().to_string())
+            .chain(args.into_iter().map(Into::into))
+            .collect();
+        self.raw_args = self.argv.clone().into_iter().peekable();
+    }
+
+    pub(crate) fn has_io(&self) -> bool {
+        self.io.is_some()
+    }
+
+    pub(crate) fn out_is_terminal(&self) -> bool {
+        !self.has_io() && io::stdout().is_terminal()
+    }
+
+    pub(crate) fn err_is_terminal(&self) -> bool {
+        !self.has_io() && io::stderr().is_terminal()
+    }
+
+    pub(crate) fn write_out(&self, text: &str) {
+        match &self.io {
+            Some(io) => {
+                let _ = io.borrow_mut().output.write_all(text.as_bytes());
+            }
+            None => output::write(output::Stream::Stdout, text),
+        }
+    }
+
+    pub(crate) fn write_err(&self, text: &str) {
+        match &self.io {
+            Some(io) => {
+                let _ = io.borrow_mut().output.write_all(text.as_bytes());
+            }
+            None => output::write(output::Stream::Stderr, text),
+        }
+    }
+
+    /* Asks through the injected sinks when present, the terminal otherwise. */
+    pub fn prompt(&self, prompt: &Prompt) -> Result<String, ParseError> {
+        match &self.io {
+            Some(io) => {
+                let mut io = io.borrow_mut();
+                let (reader, writer) = io.split();
+                prompt.ask_with(reader, writer)
+            }
+            None => prompt.ask(),
+        }
+    }
+}
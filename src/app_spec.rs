@@ -0,0 +1,109 @@
+use std::fmt::Write;
+
+use crate::{App, Arg, ArgValidator, json};
+
+impl App {
+    pub fn add_help_json_argument(&mut self) {
+        if let Some(key) = self.parser().options().prefixes.long_key("help-json") {
+            self.add_argument(
+                &key,
+                Arg::new()
+                    .help("Print the argument specification as JSON")
+                    .as_flag()
+                    .optional(),
+            );
+        }
+    }
+
+    pub(crate) fn help_json_requested(&self) -> bool {
+        self.parser()
+            .options()
+            .prefixes
+            .long_key("help-json")
+            .is_some_and(|key| self.args().all_param_iter().any(|(k, _)| key.as_str() == k))
+    }
+
+    /* Stable JSON description of the identity and every tier, meant for doc and GUI generators. */
+    pub fn spec_json(&self) -> String {
+        let identity = self.identity();
+        let mut buf = String::from("{");
+        let _ = write!(
+            buf,
+            "\"name\":{},\"description\":{},\"long_description\":{},\"version\":{},\"author\":{},\"authors\":{},\"license\":{},\"homepage\":{},\"repository\":{},\"contact\":{}",
+            json::escape(&identity.name),
+            json::escape(&identity.description),
+            optional_string(identity.long_description.as_deref()),
+            json::escape(&identity.version.to_string()),
+            optional_string(identity.authors_line().as_deref()),
+            string_array(identity.authors.iter().map(String::as_str)),
+            optional_string(identity.license.as_deref()),
+            optional_string(identity.homepage.as_deref()),
+            optional_string(identity.repository.as_deref()),
+            optional_string(identity.contact.as_deref())
+        );
+        let _ = write!(
+            buf,
+            ",\"examples\":{}",
+            string_array(identity.examples.iter().map(String::as_str))
+        );
+        buf.push_str(",\"tiers\":[");
+        for (idx, tier) in self.parser().iter().enumerate() {
+            if idx > 0 {
+                buf.push(',');
+            }
+            let _ = write!(
+                buf,
+                "{{\"index\":{},\"name\":{},\"description\":{},\"positional\":{},\"arguments\":[",
+                idx,
+                json::escape(&tier.label(idx)),
+                optional_string(tier.description()),
+                arg_json(&tier.pos)
+            );
+            for (id, (key, arg)) in tier.params_iter().enumerate() {
+                if id > 0 {
+                    buf.push(',');
+                }
+                let mut entry = arg_json(arg);
+                entry.insert_str(1, &format!("\"key\":{},", json::escape(&key.value)));
+                buf.push_str(&entry);
+            }
+            buf.push_str("]}");
+        }
+        buf.push_str("]}");
+        buf
+    }
+}
+
+fn arg_json(arg: &Arg) -> String {
+    let count = ArgValidator::count_range(arg)
+        .map(|(min, max)| match max {
+            u64::MAX => format!("{{\"min\":{},\"max\":null}}", min),
+            max => format!("{{\"min\":{},\"max\":{}}}", min, max),
+        })
+        .unwrap_or_else(|| String::from("null"));
+    let validators: Vec<String> = arg.validators_iter().filter_map(|v| v.id()).collect();
+    format!(
+        "{{\"help\":{},\"group\":{},\"flag\":{},\"default\":{},\"env\":{},\"choices\":{},\"count\":{},\"validators\":{}}}",
+        optional_string(arg.help_text()),
+        optional_string(arg.group()),
+        ArgValidator::allows_empty(arg).unwrap_or(false),
+        optional_string(ArgValidator::default_value(arg).as_deref()),
+        optional_string(ArgValidator::env_var(arg).as_deref()),
+        ArgValidator::choices(arg)
+            .map(|choices| string_array(choices.iter().map(String::as_str)))
+            .unwrap_or_else(|| String::from("null")),
+        count,
+        string_array(validators.iter().map(String::as_str))
+    )
+}
+
+fn optional_string(v: Option<&str>) -> String {
+    v.map(json::escape).unwrap_or_else(|| String::from("null"))
+}
+
+fn string_array<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    format!(
+        "[{}]",
+        values.map(json::escape).collect::<Vec<_>>().join(",")
+    )
+}
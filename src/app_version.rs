@@ -3,11 +3,14 @@ use std::fmt;
 
 use crate::ParseError;
 
-#[derive(Default, cmp::PartialEq, Copy, Debug, Clone)]
+/* Ordered by semver precedence, build metadata does not take part in comparisons. */
+#[derive(Default, Debug, Clone)]
 pub struct AppVersion {
     pub major: u32,
     pub minor: u32,
     pub patch: u32,
+    pub pre: Option<String>,
+    pub build: Option<String>,
 }
 
 impl AppVersion {
@@ -16,37 +19,107 @@ impl AppVersion {
             major,
             minor,
             patch,
+            pre: None,
+            build: None,
         }
     }
+
+    pub fn pre(mut self, pre: impl Into<String>) -> Self {
+        self.pre = Some(pre.into());
+        self
+    }
+
+    pub fn build(mut self, build: impl Into<String>) -> Self {
+        self.build = Some(build.into());
+        self
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+}
+
+fn cmp_pre(a: &str, b: &str) -> cmp::Ordering {
+    let mut a_it = a.split('.');
+    let mut b_it = b.split('.');
+    loop {
+        let ord = match (a_it.next(), b_it.next()) {
+            (None, None) => return cmp::Ordering::Equal,
+            (None, Some(_)) => return cmp::Ordering::Less,
+            (Some(_), None) => return cmp::Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => cmp::Ordering::Less,
+                (Err(_), Ok(_)) => cmp::Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ord != cmp::Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
+impl cmp::Ord for AppVersion {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => cmp::Ordering::Equal,
+                (None, Some(_)) => cmp::Ordering::Greater,
+                (Some(_), None) => cmp::Ordering::Less,
+                (Some(a), Some(b)) => cmp_pre(a, b),
+            })
+    }
 }
 
 impl cmp::PartialOrd for AppVersion {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        if self.major > other.major
-            || self.major == other.major && self.minor > other.minor
-            || self.major == other.major && self.minor == other.minor && self.patch > other.patch
-        {
-            Some(cmp::Ordering::Greater)
-        } else if self.major == other.major
-            && self.minor == other.minor
-            && self.patch == other.patch
-        {
-            Some(cmp::Ordering::Equal)
-        } else {
-            Some(cmp::Ordering::Less)
-        }
+        Some(self.cmp(other))
     }
 }
+
+impl cmp::PartialEq for AppVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
+    }
+}
+
+impl cmp::Eq for AppVersion {}
+
 impl fmt::Display for AppVersion {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{}", build)?;
+        }
+        Ok(())
     }
 }
 
+fn valid_identifiers(v: &str) -> bool {
+    v.split('.')
+        .all(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+}
+
 impl TryFrom<&str> for AppVersion {
     type Error = ParseError;
     fn try_from(v: &str) -> Result<AppVersion, ParseError> {
-        let mut split_it = v.split('.');
+        let (rest, build) = match v.split_once('+') {
+            Some((rest, build)) => (rest, Some(build)),
+            None => (v, None),
+        };
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+        if !pre.is_none_or(valid_identifiers) || !build.is_none_or(valid_identifiers) {
+            return Err(ParseError::invalid_value(format_args!("{v}")));
+        }
+        let mut split_it = core.split('.');
         let major_s = split_it.next();
         if major_s.is_none() {
             return Err(ParseError::invalid_value(format_args!("{v}")));
@@ -66,6 +139,8 @@ impl TryFrom<&str> for AppVersion {
                         major,
                         minor,
                         patch,
+                        pre: pre.map(String::from),
+                        build: build.map(String::from),
                     }),
                     Err(_) => Err(ParseError::invalid_value(format_args!("{v}"))),
                 },
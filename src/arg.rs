@@ -1,4 +1,13 @@
-use crate::{ArgKey, ParseError, ParsedArg, paragraph, tui};
+use crate::{
+    AppIdentity, AppVersion, ArgKey, ParseError, ParsedArg, ValueSource, paragraph, suggest, tui,
+};
+
+/* Outcome of a value check that did not fail, a warning is reported without aborting. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Validation {
+    Pass,
+    Warn(String),
+}
 
 pub trait ArgValidator {
     fn id(&self) -> Option<String> {
@@ -7,17 +16,55 @@ pub trait ArgValidator {
     fn validate(&self, _v: Option<&str>) -> Result<(), ParseError> {
         Ok(())
     }
+    /* Override instead of validate to accept a value with a warning. */
+    fn check_value(&self, v: Option<&str>) -> Result<Validation, ParseError> {
+        self.validate(v).map(|_| Validation::Pass)
+    }
     fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
         Ok(())
     }
+    /* Rewrites a given value before it is validated and stored. */
+    fn transform(&self, v: &str) -> String {
+        v.to_string()
+    }
     fn help(&self) -> Option<tui::DomNode> {
         None
     }
+    fn check_definition(&self, _identity: &AppIdentity) -> Result<(), ParseError> {
+        Ok(())
+    }
+    fn count_range(&self) -> Option<(u64, u64)> {
+        None
+    }
+    fn allows_empty(&self) -> Option<bool> {
+        None
+    }
+    fn default_value(&self) -> Option<String> {
+        None
+    }
+    fn env_var(&self) -> Option<String> {
+        None
+    }
+    fn choices(&self) -> Option<Vec<String>> {
+        None
+    }
+    fn captures_rest(&self) -> bool {
+        false
+    }
+    /* Keys that must also be given, checked once every tier has been parsed. */
+    fn requires(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /* Keys that must not be given together with this one, checked after the tier is parsed. */
+    fn conflicts(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct ArgOptionValidator {
     options: Vec<(String, Option<String>)>,
+    ignore_case: bool,
 }
 
 impl ArgOptionValidator {
@@ -37,6 +84,10 @@ impl ArgOptionValidator {
         }
         self
     }
+    pub fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self
+    }
     pub fn iter(&self) -> impl Iterator<Item = &(String, Option<String>)> {
         self.options.iter()
     }
@@ -46,12 +97,23 @@ impl ArgOptionValidator {
     pub fn is_empty(&self) -> bool {
         self.options.is_empty()
     }
+    /* The option v names, an exact match wins over one that differs in case. */
+    fn matching(&self, v: &str) -> Option<&String> {
+        self.iter().map(|(k, _)| k).find(|k| *k == v).or_else(|| {
+            self.iter()
+                .map(|(k, _)| k)
+                .find(|k| self.ignore_case && k.to_lowercase() == v.to_lowercase())
+        })
+    }
 }
 
 impl ArgValidator for ArgOptionValidator {
     fn id(&self) -> Option<String> {
         Some(String::from("Option"))
     }
+    fn choices(&self) -> Option<Vec<String>> {
+        Some(self.iter().map(|(v, _)| v.clone()).collect())
+    }
     fn help(&self) -> Option<tui::DomNode> {
         if self.is_empty() {
             return None;
@@ -69,15 +131,24 @@ impl ArgValidator for ArgOptionValidator {
     fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
         match v {
             None => Err(ParseError::no_value_given(format_args!(""))),
-            Some(v) => match self.iter().find(|(k, _)| k == v) {
-                None => Err(ParseError::invalid_value(format_args!(
-                    "{} is not a valid option",
-                    v
-                ))),
+            Some(v) => match self.matching(v) {
+                None => {
+                    let err =
+                        ParseError::invalid_value(format_args!("{} is not a valid option", v));
+                    match suggest::closest(v, self.iter().map(|(k, _)| k.as_str())) {
+                        Some(s) => Err(err.suggest(s)),
+                        None => Err(err),
+                    }
+                }
                 Some(_) => Ok(()),
             },
         }
     }
+    /* Stores the option as declared, so `Release` is kept as `release` under ignore_case. */
+    fn transform(&self, v: &str) -> String {
+        self.matching(v)
+            .map_or_else(|| v.to_string(), String::clone)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -113,6 +184,10 @@ impl ArgValidator for ArgCountValidator {
         Some(String::from("ArgCountValidator"))
     }
 
+    fn count_range(&self) -> Option<(u64, u64)> {
+        Some((self.min_size, self.max_size))
+    }
+
     fn help(&self) -> Option<tui::DomNode> {
         if self.min_size == self.max_size && self.min_size != 1 {
             Some(paragraph!("Arg Count: ={}", self.min_size))
@@ -133,7 +208,12 @@ impl ArgValidator for ArgCountValidator {
 
     fn post_validate(&self, key: Option<&ArgKey>, args: &mut ParsedArg) -> Result<(), ParseError> {
         let count = key.map(|k| args.count(k) as u64).unwrap_or(1);
-        if count < self.min_size || count > self.max_size {
+        if count < self.min_size {
+            Err(ParseError::too_few_value_given(format_args!(
+                "{} not in {} <= x <= {}",
+                count, self.min_size, self.max_size
+            )))
+        } else if count > self.max_size {
             Err(ParseError::too_many_value_given(format_args!(
                 "{} not in {} <= x <= {}",
                 count, self.min_size, self.max_size
@@ -168,6 +248,10 @@ impl ArgValidator for ArgEmptyValidator {
         Some(String::from("ArgEmptyValidator"))
     }
 
+    fn allows_empty(&self) -> Option<bool> {
+        Some(self.allow_empty)
+    }
+
     fn help(&self) -> Option<tui::DomNode> {
         if self.allow_empty {
             Some(paragraph!("Flag"))
@@ -209,25 +293,188 @@ impl<T: Into<String>> From<T> for DefaultArg {
 }
 
 impl ArgValidator for DefaultArg {
-    fn help(&self) -> Option<tui::DomNode> {
-        Some(paragraph!("Default: {}", self.value))
-    }
     fn id(&self) -> Option<String> {
         Some(String::from("DefaultArg"))
     }
+    fn default_value(&self) -> Option<String> {
+        Some(self.value.clone())
+    }
     fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
         if let Some(k) = _k
             && _args.count(k) == 0
         {
-            _args.add_argument(k.clone(), self.value.clone());
+            _args.add_argument_from(k.clone(), self.value.clone(), ValueSource::Default);
         }
         Ok(())
     }
 }
 
+/* Everything after the key, minus a leading `--`, is captured verbatim into ParsedArg::raw_after. */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgCaptureRest;
+
+impl ArgValidator for ArgCaptureRest {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgCaptureRest"))
+    }
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(paragraph!("Captures all remaining arguments"))
+    }
+    fn captures_rest(&self) -> bool {
+        true
+    }
+}
+
+/* Declares the environment variable consulted when the key is not given on the command line. */
+#[derive(Debug, Clone)]
+pub struct EnvArg {
+    var: String,
+}
+
+impl EnvArg {
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl ArgValidator for EnvArg {
+    fn id(&self) -> Option<String> {
+        Some(String::from("EnvArg"))
+    }
+    fn env_var(&self) -> Option<String> {
+        Some(self.var.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArgRequiresValidator {
+    key: String,
+}
+
+impl ArgRequiresValidator {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl ArgValidator for ArgRequiresValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgRequiresValidator"))
+    }
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(paragraph!("Requires: {}", self.key))
+    }
+    fn requires(&self) -> Vec<String> {
+        vec![self.key.clone()]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArgConflictValidator {
+    key: String,
+}
+
+impl ArgConflictValidator {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl ArgValidator for ArgConflictValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgConflictValidator"))
+    }
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(paragraph!("Conflicts with: {}", self.key))
+    }
+    fn conflicts(&self) -> Vec<String> {
+        vec![self.key.clone()]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeprecatedArg {
+    message: String,
+    since: Option<AppVersion>,
+    remove_in: Option<AppVersion>,
+}
+
+impl DeprecatedArg {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            since: None,
+            remove_in: None,
+        }
+    }
+
+    pub fn since(mut self, version: AppVersion) -> Self {
+        self.since = Some(version);
+        self
+    }
+
+    pub fn remove_in(mut self, version: AppVersion) -> Self {
+        self.remove_in = Some(version);
+        self
+    }
+
+    fn timeline(&self) -> String {
+        let mut timeline = String::new();
+        if let Some(since) = &self.since {
+            timeline.push_str(&format!(" since {}", since));
+        }
+        if let Some(remove_in) = &self.remove_in {
+            timeline.push_str(&format!(" and will be removed in {}", remove_in));
+        }
+        timeline
+    }
+}
+
+impl<T: Into<String>> From<T> for DeprecatedArg {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl ArgValidator for DeprecatedArg {
+    fn id(&self) -> Option<String> {
+        Some(String::from("DeprecatedArg"))
+    }
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(paragraph!(
+            "Deprecated{}: {}",
+            self.timeline(),
+            self.message
+        ))
+    }
+    /* Warns when a value is parsed, so a deprecated argument that was not given stays quiet. */
+    fn check_value(&self, _v: Option<&str>) -> Result<Validation, ParseError> {
+        Ok(Validation::Warn(format!(
+            "deprecated{}: {}",
+            self.timeline(),
+            self.message
+        )))
+    }
+    fn check_definition(&self, identity: &AppIdentity) -> Result<(), ParseError> {
+        match &self.remove_in {
+            Some(remove_in) if *remove_in < identity.version => {
+                Err(ParseError::invalid_definition(format_args!(
+                    "deprecated argument should have been removed in {}, current version is {}",
+                    remove_in, identity.version
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Arg {
     help_text: Option<String>,
+    help_group: Option<String>,
+    aliases: Vec<String>,
+    attach_value: bool,
+    secret: bool,
     validators: Vec<Box<dyn ArgValidator>>,
 }
 
@@ -237,10 +484,23 @@ impl ArgValidator for Arg {
     }
 
     fn validate(&self, value: Option<&str>) -> Result<(), ParseError> {
+        self.check_value(value).map(|_| ())
+    }
+
+    fn check_value(&self, value: Option<&str>) -> Result<Validation, ParseError> {
+        let mut warnings = Vec::new();
         for validator in &self.validators {
-            validator.validate(value)?;
+            let validation = validator
+                .check_value(value)
+                .map_err(|e| self.redact_error(e, value))?;
+            if let Validation::Warn(msg) = validation {
+                warnings.push(self.mask(msg, value));
+            }
+        }
+        match warnings.is_empty() {
+            true => Ok(Validation::Pass),
+            false => Ok(Validation::Warn(warnings.join("; "))),
         }
-        Ok(())
     }
 
     fn post_validate(&self, key: Option<&ArgKey>, args: &mut ParsedArg) -> Result<(), ParseError> {
@@ -250,10 +510,68 @@ impl ArgValidator for Arg {
         Ok(())
     }
 
+    fn transform(&self, v: &str) -> String {
+        self.validators
+            .iter()
+            .fold(v.to_string(), |v, validator| validator.transform(&v))
+    }
+
+    fn check_definition(&self, identity: &AppIdentity) -> Result<(), ParseError> {
+        for validator in &self.validators {
+            validator.check_definition(identity)?;
+        }
+        self.check_conflicts()
+    }
+
+    fn count_range(&self) -> Option<(u64, u64)> {
+        self.validators
+            .iter()
+            .filter_map(|validator| validator.count_range())
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.max(min_b), max_a.min(max_b)))
+    }
+
+    fn allows_empty(&self) -> Option<bool> {
+        self.validators
+            .iter()
+            .filter_map(|validator| validator.allows_empty())
+            .reduce(|a, b| a && b)
+    }
+
+    fn default_value(&self) -> Option<String> {
+        self.validators.iter().find_map(|v| v.default_value())
+    }
+
+    fn env_var(&self) -> Option<String> {
+        self.validators.iter().find_map(|v| v.env_var())
+    }
+
+    fn choices(&self) -> Option<Vec<String>> {
+        self.validators.iter().find_map(|v| v.choices())
+    }
+
+    fn captures_rest(&self) -> bool {
+        self.validators.iter().any(|v| v.captures_rest())
+    }
+
+    fn requires(&self) -> Vec<String> {
+        self.validators.iter().flat_map(|v| v.requires()).collect()
+    }
+
+    fn conflicts(&self) -> Vec<String> {
+        self.validators.iter().flat_map(|v| v.conflicts()).collect()
+    }
+
     fn help(&self) -> Option<tui::DomNode> {
         let mut layout = tui::Layout::default();
-        if let Some(h) = &self.help_text {
-            layout = layout.append_child(paragraph!("{}", h));
+        let mut line = self.help_text.clone().unwrap_or_default();
+        if let Some(v) = self.default_value() {
+            line = format!("{} [default: {}]", line, self.redact(&v));
+        }
+        if let Some(v) = self.env_var() {
+            line = format!("{} [env: {}]", line, v);
+        }
+        if !line.is_empty() {
+            layout = layout.append_child(paragraph!("{}", line.trim_start()));
         }
         for validator in &self.validators {
             if let Some(node) = validator.help() {
@@ -274,6 +592,71 @@ impl Arg {
         self
     }
 
+    pub fn attach_value(mut self) -> Self {
+        self.attach_value = true;
+        self
+    }
+
+    pub fn attaches_value(&self) -> bool {
+        self.attach_value && ArgValidator::allows_empty(self) != Some(true)
+    }
+
+    /* Masks the value wherever it would be echoed: errors, warnings, help and history. */
+    pub fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+
+    pub fn is_secret(&self) -> bool {
+        self.secret
+    }
+
+    pub(crate) fn mark_secret(&mut self) {
+        self.secret = true;
+    }
+
+    pub fn redact<'a>(&self, value: &'a str) -> &'a str {
+        match self.secret {
+            true => "***",
+            false => value,
+        }
+    }
+
+    fn redact_error(&self, mut err: ParseError, value: Option<&str>) -> ParseError {
+        err.msg = self.mask(err.msg, value);
+        err
+    }
+
+    fn mask(&self, msg: String, value: Option<&str>) -> String {
+        match value.filter(|v| self.secret && !v.is_empty()) {
+            Some(v) => msg.replace(v, "***"),
+            None => msg,
+        }
+    }
+
+    pub fn help_text(&self) -> Option<&str> {
+        self.help_text.as_deref()
+    }
+
+    pub fn help_group(mut self, group: impl Into<String>) -> Self {
+        self.help_group = Some(group.into());
+        self
+    }
+
+    pub fn group(&self) -> Option<&str> {
+        self.help_group.as_deref()
+    }
+
+    /* Another key for the same argument, values given through it are stored under the registered key. */
+    pub fn alias(mut self, key: impl Into<String>) -> Self {
+        self.aliases.push(key.into());
+        self
+    }
+
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
     pub fn validate(mut self, validator: impl ArgValidator + 'static) -> Self {
         self.validators.push(Box::new(validator));
         self
@@ -283,6 +666,26 @@ impl Arg {
         self.validate(DefaultArg::new(value))
     }
 
+    pub fn capture_rest(self) -> Self {
+        self.validate(ArgCaptureRest)
+    }
+
+    pub fn env(self, var: impl Into<String>) -> Self {
+        self.validate(EnvArg::new(var))
+    }
+
+    pub fn requires(self, key: impl Into<String>) -> Self {
+        self.validate(ArgRequiresValidator::new(key))
+    }
+
+    pub fn conflicts_with(self, key: impl Into<String>) -> Self {
+        self.validate(ArgConflictValidator::new(key))
+    }
+
+    pub fn deprecated(self, deprecation: impl Into<DeprecatedArg>) -> Self {
+        self.validate(deprecation.into())
+    }
+
     pub fn n_at_least(self, min_size: u64) -> Self {
         self.validate(ArgCountValidator::at_least(min_size))
     }
@@ -315,6 +718,57 @@ impl Arg {
         self.n_range(0, 1)
     }
 
+    fn check_conflicts(&self) -> Result<(), ParseError> {
+        let describe = |validator: &dyn ArgValidator, detail: String| {
+            format!(
+                "{}({})",
+                validator.id().unwrap_or_else(|| String::from("validator")),
+                detail
+            )
+        };
+        let mut empty: Option<(&dyn ArgValidator, bool)> = None;
+        let mut count: Option<(&dyn ArgValidator, (u64, u64))> = None;
+        for validator in self.validators.iter().map(|v| v.as_ref()) {
+            if let Some(allow) = validator.allows_empty() {
+                match empty {
+                    Some((first, first_allow)) if first_allow != allow => {
+                        let detail = |allow: bool| match allow {
+                            true => String::from("flag"),
+                            false => String::from("requires value"),
+                        };
+                        return Err(ParseError::invalid_definition(format_args!(
+                            "{} conflicts with {}",
+                            describe(first, detail(first_allow)),
+                            describe(validator, detail(allow))
+                        )));
+                    }
+                    Some(_) => {}
+                    None => empty = Some((validator, allow)),
+                }
+            }
+            if let Some((min, max)) = validator.count_range() {
+                match count {
+                    Some((first, (first_min, first_max))) if min > first_max || max < first_min => {
+                        return Err(ParseError::invalid_definition(format_args!(
+                            "{} conflicts with {}",
+                            describe(first, format!("{} <= n <= {}", first_min, first_max)),
+                            describe(validator, format!("{} <= n <= {}", min, max))
+                        )));
+                    }
+                    Some((first, (first_min, first_max))) => {
+                        count = Some((first, (first_min.max(min), first_max.min(max))))
+                    }
+                    None => count = Some((validator, (min, max))),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn validators_iter(&self) -> impl Iterator<Item = &dyn ArgValidator> {
+        self.validators.iter().map(|v| v.as_ref())
+    }
+
     pub fn len(&self) -> usize {
         self.validators.len()
     }
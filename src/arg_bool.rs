@@ -0,0 +1,41 @@
+use crate::{ArgKey, ArgValidator, KeyQuery, ParseError, ParsedArg, paragraph, tui};
+
+/* A flag given without a value counts as true. */
+pub fn parse_bool(v: &str) -> Result<bool, ParseError> {
+    match v.to_ascii_lowercase().as_str() {
+        "" | "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(ParseError::invalid_value(format_args!(
+            "{} is not a boolean, expected true/false, yes/no or 1/0",
+            v
+        ))),
+    }
+}
+
+/* Takes the value only when attached, --flag alone stays true and --flag=false turns it off. */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgBoolValidator;
+
+impl ArgValidator for ArgBoolValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgBoolValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(paragraph!("Boolean, true/false, yes/no or 1/0"))
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        v.map(parse_bool).transpose().map(|_| ())
+    }
+
+    fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+impl ParsedArg {
+    pub fn get_bool(&self, key: &(impl KeyQuery + ?Sized)) -> Option<bool> {
+        self.first_of(key).and_then(|v| parse_bool(v).ok())
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    AppIdentity, ArgKey, ArgValidator, ParseError, ParseErrorKind, ParsedArg, Validation,
+    paragraph, tui,
+};
+
+fn combined_help(title: &str, validators: &[Box<dyn ArgValidator>]) -> Option<tui::DomNode> {
+    let children: Vec<tui::DomNode> = validators.iter().filter_map(|v| v.help()).collect();
+    if children.is_empty() {
+        return None;
+    }
+    Some(
+        tui::Layout::new()
+            .append_child(paragraph!("{}", title))
+            .append_child(tui::VStack(
+                tui::Layout::new()
+                    .style(tui::DomStyle::new().indent(2))
+                    .append_children(children),
+            ))
+            .into(),
+    )
+}
+
+/* Passes when at least one of the validators accepts the value. */
+#[derive(Default)]
+pub struct AnyValidator {
+    validators: Vec<Box<dyn ArgValidator>>,
+}
+
+impl AnyValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn of(validators: impl IntoIterator<Item = Box<dyn ArgValidator>>) -> Self {
+        Self {
+            validators: validators.into_iter().collect(),
+        }
+    }
+
+    pub fn or(mut self, validator: impl ArgValidator + 'static) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+}
+
+impl ArgValidator for AnyValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("AnyValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        combined_help("Any of:", &self.validators)
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        self.check_value(v).map(|_| ())
+    }
+
+    fn check_value(&self, v: Option<&str>) -> Result<Validation, ParseError> {
+        let mut errors = Vec::with_capacity(self.validators.len());
+        for validator in &self.validators {
+            match validator.check_value(v) {
+                Ok(validation) => return Ok(validation),
+                Err(e) => errors.push(e),
+            }
+        }
+        if let Some(pos) = errors
+            .iter()
+            .position(|e| e.kind == ParseErrorKind::NoValueGiven)
+        {
+            return Err(errors.swap_remove(pos));
+        }
+        match errors.len() {
+            0 => Ok(Validation::Pass),
+            1 => Err(errors.remove(0)),
+            _ => Err(ParseError::invalid_value(format_args!(
+                "{}",
+                errors
+                    .iter()
+                    .map(|e| e.msg.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", or ")
+            ))),
+        }
+    }
+
+    fn post_validate(&self, k: Option<&ArgKey>, args: &mut ParsedArg) -> Result<(), ParseError> {
+        let mut first_err = None;
+        for validator in &self.validators {
+            match validator.post_validate(k, args) {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+        first_err.map_or(Ok(()), Err)
+    }
+
+    fn check_definition(&self, identity: &AppIdentity) -> Result<(), ParseError> {
+        for validator in &self.validators {
+            validator.check_definition(identity)?;
+        }
+        Ok(())
+    }
+}
+
+/* Passes when every validator accepts the value, reporting the first failure. */
+#[derive(Default)]
+pub struct AllValidator {
+    validators: Vec<Box<dyn ArgValidator>>,
+}
+
+impl AllValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn of(validators: impl IntoIterator<Item = Box<dyn ArgValidator>>) -> Self {
+        Self {
+            validators: validators.into_iter().collect(),
+        }
+    }
+
+    pub fn and(mut self, validator: impl ArgValidator + 'static) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+}
+
+impl ArgValidator for AllValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("AllValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        combined_help("All of:", &self.validators)
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        self.check_value(v).map(|_| ())
+    }
+
+    fn check_value(&self, v: Option<&str>) -> Result<Validation, ParseError> {
+        let mut warnings = Vec::new();
+        for validator in &self.validators {
+            if let Validation::Warn(msg) = validator.check_value(v)? {
+                warnings.push(msg);
+            }
+        }
+        match warnings.is_empty() {
+            true => Ok(Validation::Pass),
+            false => Ok(Validation::Warn(warnings.join("; "))),
+        }
+    }
+
+    fn post_validate(&self, k: Option<&ArgKey>, args: &mut ParsedArg) -> Result<(), ParseError> {
+        for validator in &self.validators {
+            validator.post_validate(k, args)?;
+        }
+        Ok(())
+    }
+
+    fn check_definition(&self, identity: &AppIdentity) -> Result<(), ParseError> {
+        for validator in &self.validators {
+            validator.check_definition(identity)?;
+        }
+        Ok(())
+    }
+
+    fn choices(&self) -> Option<Vec<String>> {
+        self.validators.iter().find_map(|v| v.choices())
+    }
+}
+
+/* Passes when the inner validator rejects the value, a missing value is still reported. */
+pub struct NotValidator {
+    validator: Box<dyn ArgValidator>,
+}
+
+impl NotValidator {
+    pub fn of(validator: impl ArgValidator + 'static) -> Self {
+        Self {
+            validator: Box::new(validator),
+        }
+    }
+}
+
+impl ArgValidator for NotValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("NotValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        combined_help("None of:", std::slice::from_ref(&self.validator))
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        match self.validator.check_value(v) {
+            Ok(_) => Err(ParseError::invalid_value(format_args!(
+                "{} is not allowed",
+                v.unwrap_or_default()
+            ))),
+            Err(e) if e.kind == ParseErrorKind::NoValueGiven => Err(e),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
+        Ok(())
+    }
+
+    fn check_definition(&self, identity: &AppIdentity) -> Result<(), ParseError> {
+        self.validator.check_definition(identity)
+    }
+}
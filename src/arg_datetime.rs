@@ -0,0 +1,110 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use crate::{ArgKey, ArgValidator, KeyQuery, ParseError, ParsedArg, paragraph, tui};
+
+const RFC3339: &str = "RFC3339";
+
+/* Formats are tried in order; ones without an offset are read as UTC. */
+#[derive(Debug, Clone)]
+pub struct ArgDateTimeValidator {
+    formats: Vec<String>,
+}
+
+impl Default for ArgDateTimeValidator {
+    fn default() -> Self {
+        Self::rfc3339()
+            .format("%Y-%m-%d %H:%M:%S")
+            .format("%Y-%m-%dT%H:%M:%S")
+            .format("%Y-%m-%d")
+    }
+}
+
+impl ArgDateTimeValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rfc3339() -> Self {
+        Self {
+            formats: vec![String::from(RFC3339)],
+        }
+    }
+
+    pub fn custom(format: impl Into<String>) -> Self {
+        Self {
+            formats: vec![format.into()],
+        }
+    }
+
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.formats.push(format.into());
+        self
+    }
+
+    pub fn parse(&self, v: &str) -> Result<DateTime<Utc>, ParseError> {
+        self.formats
+            .iter()
+            .find_map(|format| parse_with(v, format))
+            .ok_or_else(|| {
+                ParseError::invalid_value(format_args!(
+                    "{} is not a valid date, expected {}",
+                    v,
+                    self.formats.join(" or ")
+                ))
+            })
+    }
+}
+
+fn parse_with(v: &str, format: &str) -> Option<DateTime<Utc>> {
+    if format == RFC3339 {
+        return DateTime::parse_from_rfc3339(v)
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = DateTime::parse_from_str(v, format) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(v, format) {
+        return Some(dt.and_utc());
+    }
+    NaiveDate::parse_from_str(v, format)
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+impl ArgValidator for ArgDateTimeValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgDateTimeValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(paragraph!("Date/time, {}", self.formats.join(" or ")))
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        match v {
+            None => Err(ParseError::no_value_given(format_args!(""))),
+            Some(v) => self.parse(v).map(|_| ()),
+        }
+    }
+
+    fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+impl ParsedArg {
+    /* Uses the default formats of ArgDateTimeValidator, see get_datetime_as for custom ones. */
+    pub fn get_datetime(&self, key: &(impl KeyQuery + ?Sized)) -> Option<DateTime<Utc>> {
+        self.get_datetime_as(key, &ArgDateTimeValidator::default())
+    }
+
+    pub fn get_datetime_as(
+        &self,
+        key: &(impl KeyQuery + ?Sized),
+        validator: &ArgDateTimeValidator,
+    ) -> Option<DateTime<Utc>> {
+        self.first_of(key).and_then(|v| validator.parse(v).ok())
+    }
+}
@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use crate::{ArgKey, ArgValidator, KeyQuery, ParseError, ParsedArg, paragraph, tui};
+
+const UNITS: [(&str, Duration); 8] = [
+    ("ns", Duration::from_nanos(1)),
+    ("us", Duration::from_micros(1)),
+    ("µs", Duration::from_micros(1)),
+    ("ms", Duration::from_millis(1)),
+    ("s", Duration::from_secs(1)),
+    ("m", Duration::from_secs(60)),
+    ("h", Duration::from_secs(3600)),
+    ("d", Duration::from_secs(86400)),
+];
+
+/* A sequence of <integer><unit> parts, e.g. 1h30m or 250ms. A bare integer is read as seconds. */
+pub fn parse_duration(v: &str) -> Result<Duration, ParseError> {
+    let invalid = || {
+        ParseError::invalid_value(format_args!(
+            "{} is not a valid duration, expected e.g. 30s, 5m, 1h30m or 250ms",
+            v
+        ))
+    };
+    if let Ok(secs) = v.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+    if v.is_empty() {
+        return Err(invalid());
+    }
+    let mut rest = v;
+    let mut total = Duration::ZERO;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let amount = rest[..digits]
+            .parse::<u32>()
+            .map_err(|e| invalid().caused_by(e))?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit = UNITS
+            .iter()
+            .find(|(name, _)| *name == &rest[..unit_len])
+            .map(|(_, unit)| *unit)
+            .ok_or_else(invalid)?;
+        rest = &rest[unit_len..];
+        total = unit
+            .checked_mul(amount)
+            .and_then(|part| total.checked_add(part))
+            .ok_or_else(invalid)?;
+    }
+    Ok(total)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgDurationValidator {
+    min: Option<Duration>,
+    max: Option<Duration>,
+}
+
+impl ArgDurationValidator {
+    pub const fn new() -> Self {
+        Self {
+            min: None,
+            max: None,
+        }
+    }
+
+    pub const fn at_least(mut self, min: Duration) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub const fn at_most(mut self, max: Duration) -> Self {
+        self.max = Some(max);
+        self
+    }
+}
+
+impl ArgValidator for ArgDurationValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgDurationValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(match (self.min, self.max) {
+            (None, None) => paragraph!("Duration, e.g. 30s, 5m, 1h30m or 250ms"),
+            (Some(min), None) => paragraph!("Duration, e.g. 30s or 1h30m, >= {:?}", min),
+            (None, Some(max)) => paragraph!("Duration, e.g. 30s or 1h30m, <= {:?}", max),
+            (Some(min), Some(max)) => {
+                paragraph!("Duration, e.g. 30s or 1h30m, {:?} <= d <= {:?}", min, max)
+            }
+        })
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        let Some(v) = v else {
+            return Err(ParseError::no_value_given(format_args!("")));
+        };
+        let duration = parse_duration(v)?;
+        if let Some(min) = self.min
+            && duration < min
+        {
+            return Err(ParseError::invalid_value(format_args!(
+                "{} is shorter than {:?}",
+                v, min
+            )));
+        }
+        if let Some(max) = self.max
+            && duration > max
+        {
+            return Err(ParseError::invalid_value(format_args!(
+                "{} is longer than {:?}",
+                v, max
+            )));
+        }
+        Ok(())
+    }
+
+    fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+impl ParsedArg {
+    pub fn get_duration(&self, key: &(impl KeyQuery + ?Sized)) -> Option<Duration> {
+        self.first_of(key).and_then(|v| parse_duration(v).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::parse_duration;
+
+    #[test]
+    fn parses_units_and_sequences() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+        assert_eq!(parse_duration("1µs").unwrap(), Duration::from_micros(1));
+        assert_eq!(parse_duration("2d1ns").unwrap(), Duration::new(172800, 1));
+    }
+
+    #[test]
+    fn bare_integer_is_seconds() {
+        assert_eq!(parse_duration("0").unwrap(), Duration::ZERO);
+        assert_eq!(parse_duration("10").unwrap(), Duration::from_secs(10));
+        assert_eq!(
+            parse_duration(&u64::MAX.to_string()).unwrap(),
+            Duration::from_secs(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert!(parse_duration("18446744073709551616").is_err());
+        assert!(parse_duration("4294967296s").is_err());
+        assert!(parse_duration(&"4294967295d".repeat(50_000)).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for v in ["", "s", "10x", "1.5h", "-5s", "5 m", "h1", "1h30"] {
+            assert!(parse_duration(v).is_err(), "{v} should be rejected");
+        }
+    }
+}
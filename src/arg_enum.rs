@@ -0,0 +1,62 @@
+use crate::{Arg, ArgOptionValidator, KeyQuery, ParsedArg};
+
+/* An enum whose variants are the accepted values of an argument, see impl_arg_enum!. */
+pub trait ArgEnum: Sized + Copy + 'static {
+    fn variants() -> &'static [Self];
+    fn name(&self) -> &'static str;
+    fn help(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn from_name(v: &str) -> Option<Self> {
+        Self::variants().iter().find(|e| e.name() == v).copied()
+    }
+
+    fn validator() -> ArgOptionValidator {
+        Self::variants()
+            .iter()
+            .fold(ArgOptionValidator::new(), |validator, e| {
+                validator.option(e.name(), e.help().map(String::from))
+            })
+    }
+}
+
+impl Arg {
+    pub fn value_enum<E: ArgEnum>(self) -> Self {
+        self.validate(E::validator())
+    }
+}
+
+impl ParsedArg {
+    pub fn get_enum<E: ArgEnum>(&self, key: &(impl KeyQuery + ?Sized)) -> Option<E> {
+        self.first_of(key).and_then(|v| E::from_name(v))
+    }
+}
+
+/* impl_arg_enum!(Format { Json => "json": "one JSON document", Table => "table" }); */
+#[macro_export]
+macro_rules! impl_arg_enum {
+    (@help) => {
+        None
+    };
+    (@help $help: literal) => {
+        Some($help)
+    };
+    ($ty: ty { $($variant: ident => $name: literal $(: $help: literal)?),* $(,)? }) => {
+        impl $crate::ArgEnum for $ty {
+            fn variants() -> &'static [Self] {
+                &[$(<$ty>::$variant),*]
+            }
+            fn name(&self) -> &'static str {
+                match self {
+                    $(<$ty>::$variant => $name),*
+                }
+            }
+            fn help(&self) -> Option<&'static str> {
+                match self {
+                    $(<$ty>::$variant => $crate::impl_arg_enum!(@help $($help)?)),*
+                }
+            }
+        }
+    };
+}
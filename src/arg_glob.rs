@@ -0,0 +1,346 @@
+use std::path::Path;
+
+use crate::{ArgKey, ArgValidator, ParseError, ParsedArg, paragraph, tui};
+
+/* Supports * and ? (not crossing /), ** (any depth), [abc], [!a-z], {a,b} and \ escapes. */
+pub fn check_glob(pattern: &str) -> Result<(), ParseError> {
+    let invalid = |why: &str| {
+        ParseError::invalid_value(format_args!("{} is not a valid glob, {}", pattern, why))
+    };
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut depth = 0usize;
+    let mut id = 0;
+    while id < chars.len() {
+        match chars[id] {
+            '\\' if id + 1 == chars.len() => return Err(invalid("trailing escape")),
+            '\\' => id += 1,
+            '[' => match class_end(&chars[id + 1..]) {
+                Some(len) => id += len,
+                None => return Err(invalid("unclosed [")),
+            },
+            '{' => depth += 1,
+            '}' if depth == 0 => return Err(invalid("unmatched }")),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        id += 1;
+    }
+    match depth {
+        0 => Ok(()),
+        _ => Err(invalid("unclosed {")),
+    }
+}
+
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let path: Vec<char> = path.chars().collect();
+    expand_braces(pattern).iter().any(|pattern| {
+        let pattern: Vec<char> = pattern.chars().collect();
+        match_here(&pattern, &path)
+    })
+}
+
+/* Matching paths sorted, a pattern without wildcards is returned as-is when the path exists. */
+pub fn expand_glob(pattern: &str) -> Result<Vec<String>, ParseError> {
+    check_glob(pattern)?;
+    let mut found = Vec::new();
+    for pattern in expand_braces(pattern) {
+        let components: Vec<&str> = pattern.split('/').collect();
+        let literal = components
+            .iter()
+            .take_while(|c| !c.contains(['*', '?', '[', '\\']))
+            .count();
+        if literal == components.len() {
+            if Path::new(&pattern).exists() {
+                found.push(pattern);
+            }
+            continue;
+        }
+        let base = match components[..literal].join("/") {
+            /* A pattern rooted at the filesystem root starts with an empty component. */
+            base if base.is_empty() && literal > 0 => String::from("/"),
+            base => base,
+        };
+        let depth = match pattern.contains("**") {
+            true => usize::MAX,
+            false => components.len() - literal,
+        };
+        let chars: Vec<char> = pattern.chars().collect();
+        walk(&base, depth, &mut |path| {
+            let candidate: Vec<char> = path.chars().collect();
+            if match_here(&chars, &candidate) {
+                found.push(path.to_string());
+            }
+        });
+    }
+    found.sort();
+    found.dedup();
+    Ok(found)
+}
+
+fn walk(dir: &str, depth: usize, visit: &mut dyn FnMut(&str)) {
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = match dir {
+            "" => name,
+            "/" => format!("/{}", name),
+            dir => format!("{}/{}", dir, name),
+        };
+        visit(&path);
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            walk(&path, depth - 1, visit);
+        }
+    }
+}
+
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut id = 0;
+    let mut open = None;
+    let mut depth = 0usize;
+    let mut splits = Vec::new();
+    while id < chars.len() {
+        match chars[id] {
+            '\\' => id += 1,
+            '[' => id += class_end(&chars[id + 1..]).unwrap_or(0),
+            '{' => {
+                if depth == 0 {
+                    open = Some(id);
+                }
+                depth += 1;
+            }
+            ',' if depth == 1 => splits.push(id),
+            '}' if depth == 1 => {
+                let open = open.unwrap_or_default();
+                let prefix: String = chars[..open].iter().collect();
+                let suffix: String = chars[id + 1..].iter().collect();
+                let bounds: Vec<usize> = std::iter::once(open)
+                    .chain(splits)
+                    .chain(std::iter::once(id))
+                    .collect();
+                return bounds
+                    .windows(2)
+                    .flat_map(|w| {
+                        let alt: String = chars[w[0] + 1..w[1]].iter().collect();
+                        expand_braces(&format!("{}{}{}", prefix, alt, suffix))
+                    })
+                    .collect();
+            }
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        id += 1;
+    }
+    vec![pattern.to_string()]
+}
+
+/* Length of a [...] class body including the closing ], a leading ] is literal. */
+fn class_end(p: &[char]) -> Option<usize> {
+    let mut id = match p.first() {
+        Some('!' | '^') => 1,
+        _ => 0,
+    };
+    if p.get(id) == Some(&']') {
+        id += 1;
+    }
+    p[id.min(p.len())..]
+        .iter()
+        .position(|&c| c == ']')
+        .map(|pos| id + pos + 1)
+}
+
+fn class_match(p: &[char], c: char) -> bool {
+    let (negate, body) = match p.first() {
+        Some('!' | '^') => (true, &p[1..]),
+        _ => (false, p),
+    };
+    let mut id = 0;
+    let mut hit = false;
+    while id < body.len() {
+        if id + 2 < body.len() && body[id + 1] == '-' {
+            hit |= body[id] <= c && c <= body[id + 2];
+            id += 3;
+        } else {
+            hit |= body[id] == c;
+            id += 1;
+        }
+    }
+    hit != negate
+}
+
+fn match_here(p: &[char], s: &[char]) -> bool {
+    match p.first() {
+        None => s.is_empty(),
+        Some('*') if p.get(1) == Some(&'*') => {
+            let rest = &p[2..];
+            if rest.first() == Some(&'/') && match_here(&rest[1..], s) {
+                return true;
+            }
+            (0..=s.len()).any(|id| match_here(rest, &s[id..]))
+        }
+        Some('*') => {
+            for id in 0..=s.len() {
+                if match_here(&p[1..], &s[id..]) {
+                    return true;
+                }
+                if s.get(id) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => s.first().is_some_and(|&c| c != '/') && match_here(&p[1..], &s[1..]),
+        Some('[') => match (class_end(&p[1..]), s.first()) {
+            (Some(len), Some(&c)) => {
+                c != '/' && class_match(&p[1..len], c) && match_here(&p[len + 1..], &s[1..])
+            }
+            (None, Some(&c)) => c == '[' && match_here(&p[1..], &s[1..]),
+            (_, None) => false,
+        },
+        Some('\\') if p.len() > 1 => s.first() == Some(&p[1]) && match_here(&p[2..], &s[1..]),
+        Some(&c) => s.first() == Some(&c) && match_here(&p[1..], &s[1..]),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgGlobValidator {
+    expand: bool,
+}
+
+impl ArgGlobValidator {
+    pub const fn new() -> Self {
+        Self { expand: false }
+    }
+
+    /* Replaces each pattern with the paths it matches once parsing is done. */
+    pub const fn expand(mut self) -> Self {
+        self.expand = true;
+        self
+    }
+}
+
+impl ArgValidator for ArgGlobValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgGlobValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(match self.expand {
+            true => paragraph!("Glob pattern expanded to matching paths, e.g. src/**/*.rs"),
+            false => paragraph!("Glob pattern, e.g. src/**/*.rs or *.{{png,jpg}}"),
+        })
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        match v {
+            None => Err(ParseError::no_value_given(format_args!(""))),
+            Some(v) => check_glob(v),
+        }
+    }
+
+    fn post_validate(&self, k: Option<&ArgKey>, args: &mut ParsedArg) -> Result<(), ParseError> {
+        let Some(k) = k.filter(|_| self.expand) else {
+            return Ok(());
+        };
+        let mut paths = Vec::new();
+        for pattern in args.filter(k) {
+            let matched = expand_glob(pattern)?;
+            if matched.is_empty() {
+                return Err(ParseError::invalid_value(format_args!(
+                    "{} did not match any path",
+                    pattern
+                )));
+            }
+            paths.extend(matched);
+        }
+        if !paths.is_empty() {
+            args.replace_values(k, paths);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{check_glob, expand_glob, glob_match};
+
+    #[test]
+    fn checks_syntax() {
+        for pattern in [
+            "*.rs",
+            "src/**/*.rs",
+            "[!a-z]?",
+            "[]]",
+            "*.{png,jpg}",
+            "{a,{b,c}}",
+            "\\*",
+        ] {
+            assert!(check_glob(pattern).is_ok(), "{pattern} should be valid");
+        }
+        for pattern in ["[abc", "{a,b", "a}", "trailing\\", "{[}"] {
+            assert!(check_glob(pattern).is_err(), "{pattern} should be invalid");
+        }
+    }
+
+    #[test]
+    fn matches_wildcards_within_a_component() {
+        assert!(glob_match("*.rs", "main.rs"));
+        assert!(!glob_match("*.rs", "src/main.rs"));
+        assert!(glob_match("src/?.rs", "src/a.rs"));
+        assert!(!glob_match("?", "/"));
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn matches_any_depth() {
+        assert!(glob_match("src/**/*.rs", "src/main.rs"));
+        assert!(glob_match("src/**/*.rs", "src/a/b/c.rs"));
+        assert!(glob_match("**", "a/b"));
+        assert!(!glob_match("src/**/*.rs", "lib/main.rs"));
+    }
+
+    #[test]
+    fn matches_classes_braces_and_escapes() {
+        assert!(glob_match("[a-c]x", "bx"));
+        assert!(!glob_match("[!a-c]x", "bx"));
+        assert!(glob_match("[]]", "]"));
+        assert!(glob_match("*.{png,jpg}", "cat.jpg"));
+        assert!(!glob_match("*.{png,jpg}", "cat.gif"));
+        assert!(glob_match("\\*", "*"));
+        assert!(!glob_match("\\*", "a"));
+        assert!(glob_match("[", "["));
+    }
+
+    #[test]
+    fn expands_against_the_filesystem() {
+        let root = std::env::temp_dir().join(format!("clark-glob-{}", std::process::id()));
+        fs::create_dir_all(root.join("sub/deep")).unwrap();
+        for file in ["a.rs", "b.txt", "sub/c.rs", "sub/deep/d.rs"] {
+            fs::write(root.join(file), "").unwrap();
+        }
+        let root_str = root.to_string_lossy().into_owned();
+        let expand = |pattern: &str| {
+            expand_glob(&format!("{}/{}", root_str, pattern))
+                .unwrap()
+                .into_iter()
+                .map(|path| path[root_str.len() + 1..].to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(expand("*.rs"), ["a.rs"]);
+        assert_eq!(expand("**/*.rs"), ["a.rs", "sub/c.rs", "sub/deep/d.rs"]);
+        assert_eq!(expand("{a,b}.*"), ["a.rs", "b.txt"]);
+        assert_eq!(expand("sub/c.rs"), ["sub/c.rs"]);
+        assert!(expand("missing.rs").is_empty());
+        assert!(expand("*.md").is_empty());
+        assert!(expand_glob(&format!("{}/[a", root_str)).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt;
+
+use crate::{ArgKey, ArgValidator, ParseError, ParsedArg, json, paragraph, tui};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonShape {
+    Object,
+    Array,
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+impl fmt::Display for JsonShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Object => "object",
+            Self::Array => "array",
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Bool => "boolean",
+            Self::Null => "null",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgJsonValidator {
+    shape: Option<JsonShape>,
+}
+
+impl ArgJsonValidator {
+    pub const fn new() -> Self {
+        Self { shape: None }
+    }
+
+    pub const fn expect(shape: JsonShape) -> Self {
+        Self { shape: Some(shape) }
+    }
+
+    pub const fn object() -> Self {
+        Self::expect(JsonShape::Object)
+    }
+
+    pub const fn array() -> Self {
+        Self::expect(JsonShape::Array)
+    }
+
+    pub const fn number() -> Self {
+        Self::expect(JsonShape::Number)
+    }
+}
+
+impl ArgValidator for ArgJsonValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgJsonValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(match self.shape {
+            None => paragraph!("Inline JSON value"),
+            Some(shape) => paragraph!("Inline JSON {}", shape),
+        })
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        let Some(v) = v else {
+            return Err(ParseError::no_value_given(format_args!("")));
+        };
+        let shape = json::check(v).map_err(|e| {
+            let before = &v[..e.offset];
+            let line = before.matches('\n').count() + 1;
+            let column = before
+                .rsplit('\n')
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .count()
+                + 1;
+            ParseError::invalid_value(format_args!(
+                "invalid JSON at line {} column {}: {}",
+                line, column, e.msg
+            ))
+        })?;
+        match self.shape {
+            Some(expected) if expected != shape => Err(ParseError::invalid_value(format_args!(
+                "expected a JSON {}, found {}",
+                expected, shape
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArgJsonValidator, JsonShape};
+    use crate::ArgValidator;
+
+    fn error(v: &str) -> String {
+        ArgJsonValidator::new().validate(Some(v)).unwrap_err().msg
+    }
+
+    #[test]
+    fn accepts_every_shape() {
+        let cases = [
+            (
+                r#"{"a": [1, -2.5e3, "x\u00e9\n"], "b": {}}"#,
+                JsonShape::Object,
+            ),
+            (" [true, false, null] ", JsonShape::Array),
+            (r#""é""#, JsonShape::String),
+            ("-0.5E+2", JsonShape::Number),
+            ("false", JsonShape::Bool),
+            ("null", JsonShape::Null),
+        ];
+        for (v, shape) in cases {
+            assert!(
+                ArgJsonValidator::expect(shape).validate(Some(v)).is_ok(),
+                "{v}"
+            );
+        }
+    }
+
+    #[test]
+    fn checks_the_expected_shape() {
+        let err = ArgJsonValidator::object().validate(Some("[]")).unwrap_err();
+        assert_eq!(err.msg, "expected a JSON object, found array");
+        assert!(ArgJsonValidator::number().validate(Some("12")).is_ok());
+    }
+
+    #[test]
+    fn reports_line_and_column() {
+        assert_eq!(
+            error("{\"a\": }"),
+            "invalid JSON at line 1 column 7: expected a value"
+        );
+        assert_eq!(
+            error("{\n  \"a\": 1,\n  \"b\" 2\n}"),
+            "invalid JSON at line 3 column 7: expected ':' after the key"
+        );
+        assert_eq!(
+            error("[\"é\", x]"),
+            "invalid JSON at line 1 column 7: expected a value"
+        );
+        assert_eq!(
+            error(""),
+            "invalid JSON at line 1 column 1: unexpected end of input"
+        );
+        assert_eq!(
+            error("[1,\n"),
+            "invalid JSON at line 2 column 1: unexpected end of input"
+        );
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(ArgJsonValidator::new().validate(Some(&nested(128))).is_ok());
+        assert!(error(&nested(129)).ends_with("nesting is too deep"));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let cases = [
+            ("01", "unexpected trailing characters"),
+            ("1.", "expected a digit"),
+            ("-", "expected a digit"),
+            ("tru", "expected true, false or null"),
+            ("\"abc", "unterminated string"),
+            ("\"\\x\"", "invalid escape sequence"),
+            ("\"\\u12g4\"", "expected 4 hex digits after \\u"),
+            ("\"a\tb\"", "control character in string"),
+            ("{1: 2}", "expected a string key"),
+            ("[1 2]", "expected ',' or ']'"),
+            ("{\"a\": 1 \"b\"}", "expected ',' or '}'"),
+            ("{} {}", "unexpected trailing characters"),
+        ];
+        for (v, msg) in cases {
+            assert!(error(v).ends_with(msg), "{v:?} gave {}", error(v));
+        }
+        assert_eq!(
+            ArgJsonValidator::new().validate(None).unwrap_err().kind,
+            crate::ParseErrorKind::NoValueGiven
+        );
+    }
+}
@@ -1,51 +1,255 @@
-use std::fmt::Display;
+use std::{
+    borrow::Borrow,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    sync::{Arc, LazyLock},
+};
 
 use crate::ParseError;
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ArgKeyNormalization {
+    pub ignore_case: bool,
+    pub unify_separators: bool,
+}
+
+impl ArgKeyNormalization {
+    pub fn apply(&self, k: &str) -> String {
+        let name_beg = k.len() - k.trim_start_matches('-').len();
+        let (prefix, name) = k.split_at(name_beg);
+        let name = match self.unify_separators {
+            true => name.replace('_', "-"),
+            false => name.to_string(),
+        };
+        let name = match self.ignore_case {
+            true => name.to_lowercase(),
+            false => name,
+        };
+        format!("{}{}", prefix, name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgKeyPrefixes {
+    long: Vec<String>,
+    short: Vec<String>,
+}
+
+/* Which tokens count as keys: GNU `--name` and `-n`, Java style `-name`, or custom prefixes. */
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ArgKeyStyle {
+    #[default]
+    Gnu,
+    SingleDash,
+    Custom(ArgKeyPrefixes),
+}
+
+impl ArgKeyStyle {
+    pub fn prefixes(&self) -> ArgKeyPrefixes {
+        match self {
+            Self::Gnu => ArgKeyPrefixes::default(),
+            Self::SingleDash => ArgKeyPrefixes::new().long("-"),
+            Self::Custom(prefixes) => prefixes.clone(),
+        }
+    }
+}
+
+impl From<ArgKeyStyle> for ArgKeyPrefixes {
+    fn from(style: ArgKeyStyle) -> Self {
+        style.prefixes()
+    }
+}
+
+static DEFAULT_PREFIXES: LazyLock<ArgKeyPrefixes> = LazyLock::new(ArgKeyPrefixes::default);
+
+impl Default for ArgKeyPrefixes {
+    fn default() -> Self {
+        Self::new().long("--").short("-")
+    }
+}
+
+impl ArgKeyPrefixes {
+    pub fn new() -> Self {
+        Self {
+            long: Vec::new(),
+            short: Vec::new(),
+        }
+    }
+
+    pub fn long(mut self, prefix: impl Into<String>) -> Self {
+        self.long.push(prefix.into());
+        self
+    }
+
+    pub fn short(mut self, prefix: impl Into<String>) -> Self {
+        self.short.push(prefix.into());
+        self
+    }
+
+    pub fn is_long(&self, k: &str) -> bool {
+        self.long
+            .iter()
+            .any(|prefix| k.starts_with(prefix.as_str()) && k.len() > prefix.len())
+    }
+
+    pub fn is_short(&self, k: &str) -> bool {
+        self.short.iter().any(|prefix| {
+            k.strip_prefix(prefix.as_str())
+                .is_some_and(|name| name.chars().count() == 1)
+        })
+    }
+
+    pub fn is_arg_key(&self, k: &str) -> bool {
+        self.is_long(k) || self.is_short(k)
+    }
+
+    /* Splits `-p8080` into the short key `-p` and the attached `8080`. */
+    pub fn split_short<'a>(&self, k: &'a str) -> Option<(String, &'a str)> {
+        if self.is_long(k) {
+            return None;
+        }
+        self.short.iter().find_map(|prefix| {
+            let rest = k.strip_prefix(prefix.as_str())?;
+            let name = rest.chars().next()?;
+            let value = &rest[name.len_utf8()..];
+            match value.is_empty() {
+                true => None,
+                false => Some((format!("{}{}", prefix, name), value)),
+            }
+        })
+    }
+
+    /* Splits a non-long token into its short prefix and the rest, `-abc` into `-` and `abc`. */
+    pub fn split_prefix<'a>(&self, k: &'a str) -> Option<(&'a str, &'a str)> {
+        if self.is_long(k) {
+            return None;
+        }
+        self.short.iter().find_map(|prefix| {
+            let rest = k.strip_prefix(prefix.as_str())?;
+            match rest.is_empty() {
+                true => None,
+                false => Some((&k[..prefix.len()], rest)),
+            }
+        })
+    }
+
+    pub fn long_key(&self, name: &str) -> Option<String> {
+        self.long
+            .first()
+            .map(|prefix| format!("{}{}", prefix, name))
+    }
+
+    pub fn short_key(&self, name: char) -> Option<String> {
+        self.short
+            .first()
+            .map(|prefix| format!("{}{}", prefix, name))
+    }
+}
+
+/* The key text is shared, so cloning a registered key into every parsed value is cheap. */
 #[derive(Debug, Clone)]
 pub struct ArgKey {
-    pub value: String,
+    pub value: Arc<str>,
 }
 
 impl ArgKey {
     pub fn is_arg_key(k: &str) -> bool {
-        (k.starts_with("--") && k.len() > 2) || (k.starts_with("-") && k.len() == 2)
+        DEFAULT_PREFIXES.is_arg_key(k)
     }
 
     pub fn make(k: &str) -> Result<Self, ParseError> {
-        match Self::is_arg_key(k) {
+        Self::make_with(k, &DEFAULT_PREFIXES)
+    }
+
+    pub fn make_with(k: &str, prefixes: &ArgKeyPrefixes) -> Result<Self, ParseError> {
+        match prefixes.is_arg_key(k) {
             true => Ok(Self::make_unchecked(k)),
             false => Err(ParseError::not_argument_key(format_args!("{k}"))),
         }
     }
 
-    fn make_unchecked(k: &str) -> Self {
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    pub fn normalized(&self, normalization: &ArgKeyNormalization) -> String {
+        normalization.apply(&self.value)
+    }
+
+    pub(crate) fn make_unchecked(k: &str) -> Self {
         Self { value: k.into() }
     }
 
     pub fn parse_arg(k: &str) -> Result<(Self, Option<&str>), ParseError> {
-        if !Self::is_arg_key(k) {
+        Self::parse_arg_with(k, &DEFAULT_PREFIXES)
+    }
+
+    pub fn parse_arg_with<'a>(
+        k: &'a str,
+        prefixes: &ArgKeyPrefixes,
+    ) -> Result<(Self, Option<&'a str>), ParseError> {
+        if !prefixes.is_arg_key(k) {
             return Err(ParseError::not_argument_key(format_args!("{k}")));
         }
-        match k.find("=") {
-            None => Ok((ArgKey::make_unchecked(k), None)),
-            Some(eq_pos) => {
-                let (pre_eq, post_eq) = k.split_at(eq_pos);
-                Ok((ArgKey::make_unchecked(pre_eq), Some(&post_eq[1..])))
-            }
+        let (key, value) = Self::split_value(k);
+        Ok((ArgKey::make_unchecked(key), value))
+    }
+
+    /* Splits `--a=b=c` at the first `=` into `--a` and `b=c`, `--a=""` gives an empty value. */
+    pub fn split_value(k: &str) -> (&str, Option<&str>) {
+        match k.split_once('=') {
+            None => (k, None),
+            Some((key, value)) => (key, Some(unquote(value))),
         }
     }
 }
 
+/* Strips one pair of matching surrounding quotes, inner quotes are kept as they are. */
+pub(crate) fn unquote(v: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|q| v.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+        .unwrap_or(v)
+}
+
 impl From<ArgKey> for String {
     fn from(k: ArgKey) -> Self {
-        k.value
+        k.value.to_string()
+    }
+}
+
+/* What values can be looked up by, the str form is what the lookup indexes hash. */
+pub trait KeyQuery: PartialEq<ArgKey> {
+    fn key_str(&self) -> &str;
+}
+
+impl KeyQuery for str {
+    fn key_str(&self) -> &str {
+        self
+    }
+}
+
+impl KeyQuery for String {
+    fn key_str(&self) -> &str {
+        self
+    }
+}
+
+impl KeyQuery for ArgKey {
+    fn key_str(&self) -> &str {
+        &self.value
+    }
+}
+
+impl PartialEq<ArgKey> for String {
+    fn eq(&self, other: &ArgKey) -> bool {
+        *other.value == **self
     }
 }
 
 impl PartialEq<ArgKey> for str {
     fn eq(&self, other: &ArgKey) -> bool {
-        other.value == self
+        *other.value == *self
     }
 }
 
@@ -55,8 +259,29 @@ impl PartialEq<ArgKey> for ArgKey {
     }
 }
 
+impl Eq for ArgKey {}
+
+impl Hash for ArgKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl Borrow<str> for ArgKey {
+    fn borrow(&self) -> &str {
+        &self.value
+    }
+}
+
 impl Display for ArgKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.value)
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ArgKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.value)
+    }
+}
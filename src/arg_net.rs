@@ -0,0 +1,215 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use crate::{ArgKey, ArgValidator, KeyQuery, ParseError, ParsedArg, paragraph, tui};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpVersion {
+    #[default]
+    Any,
+    V4,
+    V6,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgIpValidator {
+    version: IpVersion,
+}
+
+impl ArgIpValidator {
+    pub const fn new(version: IpVersion) -> Self {
+        Self { version }
+    }
+
+    pub const fn any() -> Self {
+        Self::new(IpVersion::Any)
+    }
+
+    pub const fn v4() -> Self {
+        Self::new(IpVersion::V4)
+    }
+
+    pub const fn v6() -> Self {
+        Self::new(IpVersion::V6)
+    }
+}
+
+impl ArgValidator for ArgIpValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgIpValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(match self.version {
+            IpVersion::Any => paragraph!("IP address, e.g. 192.168.0.1 or ::1"),
+            IpVersion::V4 => paragraph!("IPv4 address, e.g. 192.168.0.1"),
+            IpVersion::V6 => paragraph!("IPv6 address, e.g. ::1 or fe80::1"),
+        })
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        let Some(v) = v else {
+            return Err(ParseError::no_value_given(format_args!("")));
+        };
+        let res = match self.version {
+            IpVersion::Any => v.parse::<IpAddr>().map(|_| ()),
+            IpVersion::V4 => v.parse::<Ipv4Addr>().map(|_| ()),
+            IpVersion::V6 => v.parse::<Ipv6Addr>().map(|_| ()),
+        };
+        res.map_err(|e| {
+            let expected = match self.version {
+                IpVersion::Any => "IP",
+                IpVersion::V4 => "IPv4",
+                IpVersion::V6 => "IPv6",
+            };
+            ParseError::invalid_value(format_args!("{} is not a valid {} address", v, expected))
+                .caused_by(e)
+        })
+    }
+
+    fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+/* Accepts ip:port ([v6]:port for IPv6) and, when hostnames are allowed, host:port. */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgSocketAddrValidator {
+    allow_hostname: bool,
+}
+
+impl ArgSocketAddrValidator {
+    pub const fn new() -> Self {
+        Self {
+            allow_hostname: false,
+        }
+    }
+
+    pub const fn allow_hostname(mut self) -> Self {
+        self.allow_hostname = true;
+        self
+    }
+}
+
+impl ArgValidator for ArgSocketAddrValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgSocketAddrValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(match self.allow_hostname {
+            true => paragraph!("host:port, e.g. localhost:8080, 10.0.0.1:80 or [::1]:443"),
+            false => paragraph!("ip:port, e.g. 10.0.0.1:80 or [::1]:443"),
+        })
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        let Some(v) = v else {
+            return Err(ParseError::no_value_given(format_args!("")));
+        };
+        let Err(err) = v.parse::<SocketAddr>() else {
+            return Ok(());
+        };
+        if self.allow_hostname
+            && let Some((host, port)) = v.rsplit_once(':')
+            && is_hostname(host)
+        {
+            return parse_port(port, false).map(|_| ());
+        }
+        Err(ParseError::invalid_value(format_args!(
+            "{} is not a valid {} address",
+            v,
+            match self.allow_hostname {
+                true => "host:port",
+                false => "ip:port",
+            }
+        ))
+        .caused_by(err))
+    }
+
+    fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgPortValidator {
+    allow_zero: bool,
+}
+
+impl ArgPortValidator {
+    pub const fn new() -> Self {
+        Self { allow_zero: false }
+    }
+
+    /* Port 0 asks the OS for any free port when binding. */
+    pub const fn allow_zero(mut self) -> Self {
+        self.allow_zero = true;
+        self
+    }
+}
+
+impl ArgValidator for ArgPortValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgPortValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(paragraph!(
+            "Port number, {} <= n <= 65535",
+            if self.allow_zero { 0 } else { 1 }
+        ))
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        match v {
+            None => Err(ParseError::no_value_given(format_args!(""))),
+            Some(v) => parse_port(v, self.allow_zero).map(|_| ()),
+        }
+    }
+
+    fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+fn parse_port(v: &str, allow_zero: bool) -> Result<u16, ParseError> {
+    match v.parse::<u16>() {
+        Ok(0) if !allow_zero => Err(ParseError::invalid_value(format_args!(
+            "0 is not a valid port, expected 1 <= n <= 65535"
+        ))),
+        Ok(port) => Ok(port),
+        Err(e) => Err(ParseError::invalid_value(format_args!(
+            "{} is not a valid port, expected {} <= n <= 65535",
+            v,
+            if allow_zero { 0 } else { 1 }
+        ))
+        .caused_by(e)),
+    }
+}
+
+/* RFC 1123 labels: alphanumerics and inner hyphens, at most 63 bytes each. */
+fn is_hostname(v: &str) -> bool {
+    !v.is_empty()
+        && v.len() <= 253
+        && v.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+impl ParsedArg {
+    pub fn get_ip(&self, key: &(impl KeyQuery + ?Sized)) -> Option<IpAddr> {
+        self.first_of(key).and_then(|v| v.parse().ok())
+    }
+
+    pub fn get_socket_addr(&self, key: &(impl KeyQuery + ?Sized)) -> Option<SocketAddr> {
+        self.first_of(key).and_then(|v| v.parse().ok())
+    }
+
+    pub fn get_port(&self, key: &(impl KeyQuery + ?Sized)) -> Option<u16> {
+        self.first_of(key).and_then(|v| v.parse().ok())
+    }
+}
@@ -1,10 +1,56 @@
-use std::{fmt::Debug, iter::Peekable};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{self, Debug},
+    iter::Peekable,
+    time::{Duration, Instant},
+};
 
-use crate::{Arg, ArgKey, ArgValidator, ParseError, ParseErrorKind, ParsedArg};
+use crate::{
+    Arg, ArgKey, ArgKeyNormalization, ArgKeyPrefixes, ArgKeyStyle, ArgValidator, ParseError,
+    ParseErrorKind, ParsedArg, Password, Prompt, Validation, ValueLayer, ValueSource,
+    app_io::AppIo, lexer::Token, prompt,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub prefixes: ArgKeyPrefixes,
+    pub normalization: ArgKeyNormalization,
+    pub allow_abbreviations: bool,
+    pub allow_negative_numbers: bool,
+    pub prompt_missing: bool,
+}
+
+impl ParseOptions {
+    pub fn normalize(&self, key: &str) -> String {
+        self.normalization.apply(key)
+    }
+    pub fn key_matches(&self, registered: &ArgKey, given: &ArgKey) -> bool {
+        registered == given || self.normalize(&registered.value) == self.normalize(&given.value)
+    }
+    /* `-5` or `-0.5`, which reads as a short key under the default prefixes. */
+    pub fn is_negative_number(&self, token: &str) -> bool {
+        token
+            .strip_prefix('-')
+            .filter(|n| n.starts_with(|c: char| c.is_ascii_digit() || c == '.'))
+            .is_some_and(|n| n.parse::<f64>().is_ok())
+    }
+    pub fn key_abbreviates(&self, registered: &ArgKey, given: &ArgKey) -> bool {
+        self.prefixes.is_long(&registered.value)
+            && self.prefixes.is_long(&given.value)
+            && self
+                .normalize(&registered.value)
+                .starts_with(&self.normalize(&given.value))
+    }
+}
 
 pub struct ParamTier {
     pub pos: Arg,
     params: Vec<(ArgKey, Arg)>,
+    index: HashMap<ArgKey, usize>,
+    renames: Vec<(ArgKey, ArgKey)>,
+    name: Option<String>,
+    description: Option<String>,
 }
 
 impl ParamTier {
@@ -12,9 +58,25 @@ impl ParamTier {
         Self {
             pos,
             params: Vec::new(),
+            index: HashMap::new(),
+            renames: Vec::new(),
+            name: None,
+            description: None,
         }
     }
 
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    pub fn label(&self, pos_id: usize) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("arg{}", pos_id))
+    }
+
     pub fn len(&self) -> usize {
         self.params.len()
     }
@@ -24,33 +86,115 @@ impl ParamTier {
     pub fn params_iter(&self) -> impl Iterator<Item = &(ArgKey, Arg)> {
         self.params.iter()
     }
+    pub fn renames_iter(&self) -> impl Iterator<Item = &(ArgKey, ArgKey)> {
+        self.renames.iter()
+    }
+
+    pub fn find_param(
+        &self,
+        key: &ArgKey,
+        options: &ParseOptions,
+    ) -> Result<Option<&(ArgKey, Arg)>, ParseError> {
+        if let Some(param) = self
+            .index
+            .get(key.as_str())
+            .map(|id| &self.params[*id])
+            .or_else(|| {
+                self.params
+                    .iter()
+                    .find(|(arg_key, _)| options.key_matches(arg_key, key))
+            })
+            .or_else(|| {
+                self.index
+                    .iter()
+                    .find(|(alias, _)| options.key_matches(alias, key))
+                    .map(|(_, id)| &self.params[*id])
+            })
+        {
+            return Ok(Some(param));
+        }
+        if !options.allow_abbreviations {
+            return Ok(None);
+        }
+        let candidates: Vec<&(ArgKey, Arg)> = self
+            .params
+            .iter()
+            .filter(|(arg_key, _)| options.key_abbreviates(arg_key, key))
+            .collect();
+        match candidates.as_slice() {
+            [] => Ok(None),
+            [param] => Ok(Some(param)),
+            _ => Err(ParseError::ambiguous_argument(format_args!(
+                "{} matches {}",
+                key,
+                candidates
+                    .iter()
+                    .map(|(arg_key, _)| arg_key.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+
+    /* A negative number that no parameter of this tier is registered as, so it is a value. */
+    fn is_negative_value(&self, token: &str, options: &ParseOptions) -> bool {
+        options.allow_negative_numbers
+            && options.is_negative_number(token)
+            && ArgKey::make_with(token, &options.prefixes)
+                .ok()
+                .is_none_or(|key| matches!(self.find_param(&key, options), Ok(None)))
+    }
+
+    fn split_attached<'a>(
+        &self,
+        token: &'a str,
+        options: &ParseOptions,
+    ) -> Option<(ArgKey, Option<&'a str>)> {
+        let (key, value) = options.prefixes.split_short(token)?;
+        let key = ArgKey::make_with(&key, &options.prefixes).ok()?;
+        match self.find_param(&key, options) {
+            Ok(Some((_, arg))) if arg.attaches_value() => Some((key, Some(value))),
+            _ => None,
+        }
+    }
 
     fn parse_params(
         &self,
         key: &ArgKey,
         value: Option<&str>,
         args: &mut ParsedArg,
-        raw_args: &mut Peekable<std::env::Args>,
+        raw_args: &mut Peekable<impl Iterator<Item = String>>,
+        options: &ParseOptions,
     ) -> Result<bool, ParseError> {
-        for (arg_key, arg) in self.params_iter() {
-            if arg_key == key {
-                let parse_res = match ArgValidator::validate(arg, value) {
-                    Ok(_) => Ok(value.map(String::from)),
-                    Err(e) => match e.kind {
-                        ParseErrorKind::NoValueGiven => {
-                            raw_args.next();
-                            match ArgValidator::validate(arg, raw_args.peek().map(|v| v as &str)) {
-                                Ok(_) => Ok(raw_args.peek().cloned()),
-                                Err(e) => Err(e),
-                            }
-                        }
-                        _ => Err(e),
-                    },
-                }?;
-                args.add_argument(key.clone(), parse_res.unwrap_or_default());
+        if let Some((arg_key, arg)) = self.find_param(key, options)? {
+            if ArgValidator::captures_rest(arg) {
                 raw_args.next();
+                if value.is_none() {
+                    raw_args.next_if(|v| v == "--");
+                }
+                let mut rest: Vec<String> = value.map(String::from).into_iter().collect();
+                rest.extend(raw_args.by_ref());
+                args.add_argument(arg_key.clone(), "");
+                args.add_raw(arg_key.clone(), rest);
                 return Ok(true);
             }
+            let value = value.map(|v| ArgValidator::transform(arg, v));
+            let (parse_res, validation) = match ArgValidator::check_value(arg, value.as_deref()) {
+                Ok(validation) => Ok((value, validation)),
+                Err(e) => match e.kind {
+                    ParseErrorKind::NoValueGiven => {
+                        raw_args.next();
+                        let next = raw_args.peek().map(|v| ArgValidator::transform(arg, v));
+                        ArgValidator::check_value(arg, next.as_deref())
+                            .map(|validation| (next, validation))
+                    }
+                    _ => Err(e),
+                },
+            }?;
+            args.add_argument(arg_key.clone(), parse_res.unwrap_or_default());
+            add_validation_warning(args, arg_key, validation);
+            raw_args.next();
+            return Ok(true);
         }
         Ok(false)
     }
@@ -59,19 +203,41 @@ impl ParamTier {
         &self,
         pos_id: usize,
         args: &mut ParsedArg,
-        raw_args: &mut Peekable<std::env::Args>,
+        raw_args: &mut Peekable<impl Iterator<Item = String>>,
         parse_positional: bool,
+        options: &ParseOptions,
+    ) -> Result<(), ParseError> {
+        self.parse_values(pos_id, args, raw_args, parse_positional, options)?;
+        self.apply_layers(pos_id, args, &[], options)?;
+        self.post_validate(args)
+    }
+
+    pub fn parse_values(
+        &self,
+        pos_id: usize,
+        args: &mut ParsedArg,
+        raw_args: &mut Peekable<impl Iterator<Item = String>>,
+        parse_positional: bool,
+        options: &ParseOptions,
     ) -> Result<(), ParseError> {
         if parse_positional && let Some(current_arg) = raw_args.peek() {
-            if ArgKey::is_arg_key(current_arg) {
+            if pos_id > 0
+                && options.prefixes.is_arg_key(current_arg)
+                && !self.is_negative_value(current_arg, options)
+            {
                 return Err(ParseError::invalid_value(format_args!(
                     "expected args instead of kwargs"
                 ))
                 .key(format!("arg{}", pos_id)));
             }
-            ArgValidator::validate(&self.pos, Some(current_arg))
+            let current_arg = ArgValidator::transform(&self.pos, current_arg);
+            let validation = ArgValidator::check_value(&self.pos, Some(&current_arg))
                 .map_err(|e| e.key(format!("arg{}", pos_id)))?;
-            args.add_positional_argument(current_arg.clone());
+            args.add_positional_argument(current_arg);
+            if self.pos.is_secret() {
+                args.mark_secret_positional();
+            }
+            add_validation_warning(args, format!("arg{}", pos_id), validation);
             ArgValidator::post_validate(&self.pos, None, args)
                 .map_err(|e| e.key(format!("arg{}", pos_id)))?;
             raw_args.next();
@@ -79,27 +245,227 @@ impl ParamTier {
         let mut is_parser_run = true;
         while is_parser_run && let Some(current_arg) = raw_args.peek().cloned() {
             is_parser_run = false;
-            if let Ok((parsed_key, parsed_value)) = ArgKey::parse_arg(&current_arg) {
+            let token = Token::lex(&current_arg, &options.prefixes);
+            if let Some((parsed_key, parsed_value)) = token
+                .key()
+                .map(|key| (key, token.value()))
+                .or_else(|| self.split_attached(&current_arg, options))
+            {
+                let rename = self
+                    .renames
+                    .iter()
+                    .find(|(old, _)| options.key_matches(old, &parsed_key));
+                let key = rename.map(|(_, new)| new).unwrap_or(&parsed_key);
                 is_parser_run = self
-                    .parse_params(&parsed_key, parsed_value, args, raw_args)
-                    .map_err(|e| e.key(parsed_key))?;
+                    .parse_params(key, parsed_value, args, raw_args, options)
+                    .map_err(|e| e.key(parsed_key.clone()))?;
+                if is_parser_run && let Some((old, new)) = rename {
+                    args.add_warning(format!("{} is deprecated, use {} instead", old, new));
+                }
             }
         }
+        Ok(())
+    }
+
+    pub fn apply_layers(
+        &self,
+        pos_id: usize,
+        args: &mut ParsedArg,
+        layers: &[ValueLayer],
+        options: &ParseOptions,
+    ) -> Result<(), ParseError> {
+        self.apply_layers_with_io(pos_id, args, layers, options, None)
+    }
+
+    /* Missing values are prompted for through io when App::with_io injected it. */
+    pub(crate) fn apply_layers_with_io(
+        &self,
+        pos_id: usize,
+        args: &mut ParsedArg,
+        layers: &[ValueLayer],
+        options: &ParseOptions,
+        io: Option<&RefCell<AppIo>>,
+    ) -> Result<(), ParseError> {
+        if args.len() != pos_id + 1 {
+            return Ok(());
+        }
         for (arg_key, arg) in self.params.iter() {
+            if args.contains(arg_key) {
+                continue;
+            }
+            if let Some(var) = ArgValidator::env_var(arg)
+                && let Ok(value) = std::env::var(&var)
+            {
+                let value = ArgValidator::transform(arg, &value);
+                match ArgValidator::check_value(arg, Some(&value)) {
+                    Ok(validation) => {
+                        args.add_argument_from(arg_key.clone(), value, ValueSource::Env);
+                        add_validation_warning(args, arg_key, validation);
+                        continue;
+                    }
+                    Err(e) => {
+                        args.add_warning(format!(
+                            "{}={} is ignored for {}: {}",
+                            var,
+                            arg.redact(&value),
+                            arg_key,
+                            e.msg
+                        ));
+                    }
+                }
+            }
+            if let Some((source, value)) = layers.iter().find_map(|layer| {
+                layer
+                    .iter()
+                    .find(|(k, _)| options.key_matches(arg_key, k))
+                    .map(|(_, v)| (layer.source, v))
+            }) {
+                let value = ArgValidator::transform(arg, value);
+                let validation = ArgValidator::check_value(arg, Some(&value))
+                    .map_err(|e| e.key(arg_key.clone()))?;
+                args.add_argument_from(arg_key.clone(), value, source);
+                add_validation_warning(args, arg_key, validation);
+                continue;
+            }
+            if options.prompt_missing
+                && is_required(arg)
+                && (io.is_some() || prompt::is_interactive())
+            {
+                prompt_value(arg_key, arg, args, io)?;
+            }
+        }
+        Ok(())
+    }
+
+    /* Keys and aliases shared by two arguments, only the one added last can be reached by it. */
+    pub fn check_aliases(&self) -> Result<(), ParseError> {
+        for (id, (key, arg)) in self.params.iter().enumerate() {
+            let taken = std::iter::once(key.as_str())
+                .chain(arg.aliases().iter().map(String::as_str))
+                .find_map(|k| match self.index.get(k) {
+                    Some(&other) if other != id => Some((k, &self.params[other].0)),
+                    _ => None,
+                });
+            if let Some((k, other)) = taken {
+                return Err(ParseError::invalid_definition(format_args!(
+                    "{} of {} is also used by {}",
+                    k, key, other
+                ))
+                .key(key.clone())
+                .related_key(other.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn post_validate(&self, args: &mut ParsedArg) -> Result<(), ParseError> {
+        for (arg_key, arg) in self.params.iter() {
+            if arg.is_secret() {
+                args.mark_secret(arg_key);
+            }
             ArgValidator::post_validate(arg, Some(arg_key), args)
                 .map_err(|e| e.key(arg_key.clone()))?;
         }
+        self.check_conflicts(args)
+    }
+
+    /* Runs after every post_validate so injected defaults are in place and ignored either way. */
+    fn check_conflicts(&self, args: &ParsedArg) -> Result<(), ParseError> {
+        for (arg_key, arg) in self.params.iter() {
+            if !args.is_given(arg_key) {
+                continue;
+            }
+            if let Some(other) = ArgValidator::conflicts(arg)
+                .into_iter()
+                .find(|other| args.is_given(other.as_str()))
+            {
+                return Err(ParseError::conflicting_argument(format_args!(
+                    "{} cannot be used with {}",
+                    arg_key, other
+                ))
+                .key(arg_key.clone())
+                .related_key(other));
+            }
+        }
         Ok(())
     }
 }
 
+fn is_required(arg: &Arg) -> bool {
+    ArgValidator::count_range(arg).is_some_and(|(min, _)| min > 0)
+        && ArgValidator::default_value(arg).is_none()
+}
+
+/* Secret values are read without echo, both go through the injected io when there is one. */
+fn prompt_value(
+    arg_key: &ArgKey,
+    arg: &Arg,
+    args: &mut ParsedArg,
+    io: Option<&RefCell<AppIo>>,
+) -> Result<(), ParseError> {
+    let message = format!("Enter value for {}", arg_key);
+    let ask = || match (io, arg.is_secret()) {
+        (Some(io), true) => {
+            let mut io = io.borrow_mut();
+            let (reader, writer) = io.split();
+            Password::new(format!("{}: ", message))
+                .ask_with(reader, writer)
+                .map(|secret| secret.into_inner())
+        }
+        (Some(io), false) => {
+            let mut io = io.borrow_mut();
+            let (reader, writer) = io.split();
+            Prompt::new(&message).ask_with(reader, writer)
+        }
+        (None, true) => Password::new(format!("{}: ", message))
+            .ask()
+            .map(|secret| secret.into_inner()),
+        (None, false) => Prompt::new(&message).ask(),
+    };
+    let refused = |e: &ParseError| match io {
+        Some(io) => {
+            let mut io = io.borrow_mut();
+            let (_, writer) = io.split();
+            let _ = writeln!(writer, "{}", e.msg);
+            true
+        }
+        None => prompt::report_refused(e),
+    };
+    let (value, validation) =
+        prompt::ask_until_valid(arg, ask, refused).map_err(|e| e.key(arg_key.clone()))?;
+    args.add_argument_from(arg_key.clone(), value, ValueSource::Prompt);
+    add_validation_warning(args, arg_key, validation);
+    Ok(())
+}
+
+fn add_validation_warning(args: &mut ParsedArg, key: impl fmt::Display, validation: Validation) {
+    if let Validation::Warn(msg) = validation {
+        args.add_warning(format!("{}: {}", key, msg));
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParseTimings {
+    pub parse: Duration,
+    pub validate: Duration,
+    pub tiers: usize,
+}
+
 pub struct ArgParser {
     args: Vec<ParamTier>,
+    options: ParseOptions,
+    layers: Vec<ValueLayer>,
+    redactions: Vec<String>,
 }
 
 impl Default for ArgParser {
     fn default() -> Self {
-        let mut parser = Self { args: Vec::new() };
+        let mut parser = Self {
+            args: Vec::new(),
+            options: ParseOptions::default(),
+            layers: Vec::new(),
+            redactions: Vec::new(),
+        };
         parser.add_positional_argument(Arg::new().require_value());
         parser
     }
@@ -113,25 +479,100 @@ impl ArgParser {
         self.args.push(ParamTier::new(arg));
     }
 
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+    pub fn options_mut(&mut self) -> &mut ParseOptions {
+        &mut self.options
+    }
+    pub fn ignore_case(&mut self, enabled: bool) {
+        self.options.normalization.ignore_case = enabled;
+    }
+    pub fn unify_separators(&mut self, enabled: bool) {
+        self.options.normalization.unify_separators = enabled;
+    }
+    pub fn allow_abbreviations(&mut self, enabled: bool) {
+        self.options.allow_abbreviations = enabled;
+    }
+    pub fn allow_negative_numbers(&mut self, enabled: bool) {
+        self.options.allow_negative_numbers = enabled;
+    }
+    pub fn prompt_missing(&mut self, enabled: bool) {
+        self.options.prompt_missing = enabled;
+    }
+    pub fn key_prefixes(&mut self, prefixes: ArgKeyPrefixes) {
+        self.options.prefixes = prefixes;
+    }
+    /* Set before adding arguments, keys are checked against the style when registered. */
+    pub fn key_style(&mut self, style: ArgKeyStyle) {
+        self.options.prefixes = style.prefixes();
+    }
+
+    /* Layers are consulted in insertion order for keys not given on the command line. */
+    pub fn add_value_layer(&mut self, layer: ValueLayer) {
+        self.layers.push(layer);
+    }
+    pub fn value_layers(&self) -> &[ValueLayer] {
+        &self.layers
+    }
+
+    /* Marks every argument whose key matches the `*` wildcard pattern as secret, now and later. */
+    pub fn redact_keys(&mut self, pattern: impl Into<String>) {
+        let pattern = pattern.into();
+        for tier in self.args.iter_mut() {
+            for (key, arg) in tier.params.iter_mut() {
+                if wildcard_match(&pattern, &key.value) {
+                    arg.mark_secret();
+                }
+            }
+        }
+        self.redactions.push(pattern);
+    }
+
     pub fn add_argument(&mut self, k: &str, mut arg: Arg) {
-        match self
-            .args
-            .last_mut()
-            .unwrap()
-            .params
-            .iter_mut()
-            .find(|(arg_key, _)| k == arg_key)
-        {
+        if self.redactions.iter().any(|p| wildcard_match(p, k)) {
+            arg.mark_secret();
+        }
+        let tier = self.args.last_mut().unwrap();
+        let aliases: Vec<ArgKey> = arg
+            .aliases()
+            .iter()
+            .map(|alias| ArgKey::make_with(alias, &self.options.prefixes).unwrap())
+            .collect();
+        /* Only an existing key is replaced, taking another argument's alias is a definition error. */
+        let id = match tier.params.iter().position(|(key, _)| key.as_str() == k) {
             None => {
-                self.args
-                    .last_mut()
-                    .unwrap()
-                    .params
-                    .push((ArgKey::make(k).unwrap(), arg));
+                let key = ArgKey::make_with(k, &self.options.prefixes).unwrap();
+                tier.index.insert(key.clone(), tier.params.len());
+                tier.params.push((key, arg));
+                tier.params.len() - 1
             }
-            Some((_, cur_arg)) => {
-                std::mem::swap(cur_arg, &mut arg);
+            Some(id) => {
+                tier.params[id].1 = arg;
+                tier.index.retain(|key, at| *at != id || key.as_str() == k);
+                id
             }
+        };
+        for alias in aliases {
+            tier.index.insert(alias, id);
+        }
+    }
+
+    pub fn tier_name(&mut self, name: impl Into<String>) {
+        self.args.last_mut().unwrap().name = Some(name.into());
+    }
+
+    pub fn tier_description(&mut self, description: impl Into<String>) {
+        self.args.last_mut().unwrap().description = Some(description.into());
+    }
+
+    pub fn add_rename(&mut self, old: &str, new: &str) {
+        let old = ArgKey::make_with(old, &self.options.prefixes).unwrap();
+        let new = ArgKey::make_with(new, &self.options.prefixes).unwrap();
+        let tier = self.args.last_mut().unwrap();
+        match tier.renames.iter_mut().find(|(k, _)| k == &old) {
+            None => tier.renames.push((old, new)),
+            Some((_, cur_new)) => *cur_new = new,
         }
     }
 
@@ -145,21 +586,71 @@ impl ArgParser {
     pub fn incremental_parse(
         &self,
         args: &mut ParsedArg,
-        raw_args: &mut Peekable<std::env::Args>,
+        raw_args: &mut Peekable<impl Iterator<Item = String>>,
     ) -> Result<(), ParseError> {
+        self.incremental_parse_timed(args, raw_args).map(|_| ())
+    }
+    pub fn incremental_parse_timed(
+        &self,
+        args: &mut ParsedArg,
+        raw_args: &mut Peekable<impl Iterator<Item = String>>,
+    ) -> Result<ParseTimings, ParseError> {
+        self.incremental_parse_with_io(args, raw_args, None)
+    }
+    pub(crate) fn incremental_parse_with_io(
+        &self,
+        args: &mut ParsedArg,
+        raw_args: &mut Peekable<impl Iterator<Item = String>>,
+        io: Option<&RefCell<AppIo>>,
+    ) -> Result<ParseTimings, ParseError> {
         let arg_beg_id = match args.len() {
             0 => 0,
             v => v - 1,
         };
+        let mut timings = ParseTimings::default();
         for i in arg_beg_id..self.len() {
-            self.args[i].parse(i, args, raw_args, args.len() <= i)?
+            let begin = Instant::now();
+            self.args[i].parse_values(i, args, raw_args, args.len() <= i, &self.options)?;
+            self.args[i].apply_layers_with_io(i, args, &self.layers, &self.options, io)?;
+            let parsed = Instant::now();
+            self.args[i].post_validate(args)?;
+            timings.parse += parsed - begin;
+            timings.validate += parsed.elapsed();
+            timings.tiers += 1;
         }
-        Ok(())
+        Ok(timings)
     }
-    pub fn parse(&self, raw_args: &mut Peekable<std::env::Args>) -> Result<ParsedArg, ParseError> {
+    pub fn parse(
+        &self,
+        raw_args: &mut Peekable<impl Iterator<Item = String>>,
+    ) -> Result<ParsedArg, ParseError> {
         let mut args = ParsedArg::new();
-        self.incremental_parse(&mut args, raw_args)
-            .map(move |()| args)
+        self.incremental_parse(&mut args, raw_args)?;
+        self.check_requirements(&args)?;
+        Ok(args)
+    }
+
+    /* Arg::requires across the whole ParsedArg, so the required key may come from any tier. */
+    pub fn check_requirements(&self, args: &ParsedArg) -> Result<(), ParseError> {
+        for tier in self.args.iter().take(args.len()) {
+            for (arg_key, arg) in tier.params.iter() {
+                if !args.is_given(arg_key) {
+                    continue;
+                }
+                if let Some(other) = ArgValidator::requires(arg)
+                    .into_iter()
+                    .find(|other| !args.is_given(other.as_str()))
+                {
+                    return Err(ParseError::missing_required_argument(format_args!(
+                        "{} requires {}",
+                        arg_key, other
+                    ))
+                    .key(arg_key.clone())
+                    .related_key(other));
+                }
+            }
+        }
+        Ok(())
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &ParamTier> {
@@ -178,3 +669,22 @@ impl Debug for ArgParser {
         Ok(())
     }
 }
+
+/* Case-insensitive `*` wildcard match against the key with or without its prefix. */
+pub(crate) fn wildcard_match(pattern: &str, key: &str) -> bool {
+    fn matches(pattern: &[char], text: &[char]) -> bool {
+        match pattern.split_first() {
+            None => text.is_empty(),
+            Some(('*', rest)) => (0..=text.len()).any(|id| matches(rest, &text[id..])),
+            Some((c, rest)) => text
+                .split_first()
+                .is_some_and(|(t, text)| t == c && matches(rest, text)),
+        }
+    }
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let key = key.to_lowercase();
+    let bare = key.trim_start_matches(|c: char| !c.is_alphanumeric());
+    [key.as_str(), bare]
+        .iter()
+        .any(|k| matches(&pattern, &k.chars().collect::<Vec<_>>()))
+}
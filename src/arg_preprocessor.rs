@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use crate::ParseError;
+
+pub trait ArgPreprocessor {
+    fn process(&self, args: Vec<String>) -> Result<Vec<String>, ParseError>;
+}
+
+impl<F: Fn(Vec<String>) -> Result<Vec<String>, ParseError>> ArgPreprocessor for F {
+    fn process(&self, args: Vec<String>) -> Result<Vec<String>, ParseError> {
+        self(args)
+    }
+}
+
+/*
+  Expands `@file` tokens into the whitespace separated tokens of the file. Quotes group
+  whitespace, backslash escapes the next character outside single quotes and `#` starts a
+  comment. Nested response files resolve relative to the file that references them.
+*/
+#[derive(Debug, Clone)]
+pub struct ResponseFileExpander {
+    prefix: char,
+    max_depth: usize,
+}
+
+impl Default for ResponseFileExpander {
+    fn default() -> Self {
+        Self {
+            prefix: '@',
+            max_depth: 8,
+        }
+    }
+}
+
+impl ResponseFileExpander {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn prefix(mut self, prefix: char) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    fn expand(
+        &self,
+        args: Vec<String>,
+        base: Option<&Path>,
+        depth: usize,
+    ) -> Result<Vec<String>, ParseError> {
+        let mut expanded = Vec::with_capacity(args.len());
+        for arg in args {
+            let Some(file) = arg.strip_prefix(self.prefix).filter(|f| !f.is_empty()) else {
+                expanded.push(arg);
+                continue;
+            };
+            if depth >= self.max_depth {
+                return Err(ParseError::invalid_value(format_args!(
+                    "response files nested deeper than {}",
+                    self.max_depth
+                ))
+                .key(arg.clone()));
+            }
+            let path = match base {
+                Some(dir) => dir.join(file),
+                None => PathBuf::from(file),
+            };
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                ParseError::invalid_value(format_args!("cannot read {}: {}", path.display(), e))
+                    .key(arg.clone())
+            })?;
+            let tokens = split_tokens(&content).map_err(|e| e.key(arg.clone()))?;
+            expanded.extend(self.expand(tokens, path.parent(), depth + 1)?);
+        }
+        Ok(expanded)
+    }
+}
+
+impl ArgPreprocessor for ResponseFileExpander {
+    fn process(&self, args: Vec<String>) -> Result<Vec<String>, ParseError> {
+        self.expand(args, None, 0)
+    }
+}
+
+pub(crate) fn split_tokens(content: &str) -> Result<Vec<String>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut token: Option<String> = None;
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                let current = token.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => break,
+                        Some('\\') if c == '"' => current.extend(chars.next()),
+                        Some(v) => current.push(v),
+                        None => {
+                            return Err(ParseError::invalid_value(format_args!(
+                                "unterminated {} quote",
+                                c
+                            )));
+                        }
+                    }
+                }
+            }
+            '\\' => token.get_or_insert_with(String::new).extend(chars.next()),
+            '#' if token.is_none() => {
+                chars.by_ref().find(|c| *c == '\n');
+            }
+            c if c.is_whitespace() => tokens.extend(token.take()),
+            c => token.get_or_insert_with(String::new).push(c),
+        }
+    }
+    tokens.extend(token);
+    Ok(tokens)
+}
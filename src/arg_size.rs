@@ -0,0 +1,156 @@
+use crate::{ArgKey, ArgValidator, KeyQuery, ParseError, ParsedArg, paragraph, tui};
+
+/* KB/MB/... are decimal, KiB/MiB/... and the bare K/M/G/T/P are binary, as with dd. */
+const UNITS: [(&str, u64); 16] = [
+    ("b", 1),
+    ("k", 1 << 10),
+    ("kb", 1_000),
+    ("kib", 1 << 10),
+    ("m", 1 << 20),
+    ("mb", 1_000_000),
+    ("mib", 1 << 20),
+    ("g", 1 << 30),
+    ("gb", 1_000_000_000),
+    ("gib", 1 << 30),
+    ("t", 1 << 40),
+    ("tb", 1_000_000_000_000),
+    ("tib", 1 << 40),
+    ("p", 1 << 50),
+    ("pb", 1_000_000_000_000_000),
+    ("pib", 1 << 50),
+];
+
+pub fn parse_bytes(v: &str) -> Result<u64, ParseError> {
+    let invalid = || {
+        ParseError::invalid_value(format_args!(
+            "{} is not a valid size, expected e.g. 512, 10KB, 2MiB or 1G",
+            v
+        ))
+    };
+    let digits = v.find(|c: char| !c.is_ascii_digit()).unwrap_or(v.len());
+    let amount = v[..digits]
+        .parse::<u64>()
+        .map_err(|e| invalid().caused_by(e))?;
+    let unit = v[digits..].trim_start();
+    if unit.is_empty() {
+        return Ok(amount);
+    }
+    UNITS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+        .and_then(|(_, scale)| amount.checked_mul(*scale))
+        .ok_or_else(invalid)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgSizeValidator {
+    min: Option<u64>,
+    max: Option<u64>,
+}
+
+impl ArgSizeValidator {
+    pub const fn new() -> Self {
+        Self {
+            min: None,
+            max: None,
+        }
+    }
+
+    pub const fn at_least(mut self, min: u64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub const fn at_most(mut self, max: u64) -> Self {
+        self.max = Some(max);
+        self
+    }
+}
+
+impl ArgValidator for ArgSizeValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgSizeValidator"))
+    }
+
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(match (self.min, self.max) {
+            (None, None) => paragraph!("Size in bytes, e.g. 512, 10KB, 2MiB or 1G"),
+            (Some(min), None) => paragraph!("Size in bytes, e.g. 10KB or 2MiB, >= {}", min),
+            (None, Some(max)) => paragraph!("Size in bytes, e.g. 10KB or 2MiB, <= {}", max),
+            (Some(min), Some(max)) => {
+                paragraph!("Size in bytes, e.g. 10KB or 2MiB, {} <= n <= {}", min, max)
+            }
+        })
+    }
+
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        let Some(v) = v else {
+            return Err(ParseError::no_value_given(format_args!("")));
+        };
+        let size = parse_bytes(v)?;
+        if let Some(min) = self.min
+            && size < min
+        {
+            return Err(ParseError::invalid_value(format_args!(
+                "{} is smaller than {} bytes",
+                v, min
+            )));
+        }
+        if let Some(max) = self.max
+            && size > max
+        {
+            return Err(ParseError::invalid_value(format_args!(
+                "{} is larger than {} bytes",
+                v, max
+            )));
+        }
+        Ok(())
+    }
+
+    fn post_validate(&self, _k: Option<&ArgKey>, _args: &mut ParsedArg) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+impl ParsedArg {
+    pub fn get_bytes(&self, key: &(impl KeyQuery + ?Sized)) -> Option<u64> {
+        self.first_of(key).and_then(|v| parse_bytes(v).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_bytes;
+
+    #[test]
+    fn parses_decimal_and_binary_units() {
+        assert_eq!(parse_bytes("512").unwrap(), 512);
+        assert_eq!(parse_bytes("10KB").unwrap(), 10_000);
+        assert_eq!(parse_bytes("2MiB").unwrap(), 2 << 20);
+        assert_eq!(parse_bytes("1G").unwrap(), 1 << 30);
+        assert_eq!(parse_bytes("3b").unwrap(), 3);
+        assert_eq!(parse_bytes("4 kib").unwrap(), 4096);
+    }
+
+    #[test]
+    fn accepts_the_boundaries() {
+        assert_eq!(parse_bytes("0").unwrap(), 0);
+        assert_eq!(parse_bytes("0PiB").unwrap(), 0);
+        assert_eq!(parse_bytes(&u64::MAX.to_string()).unwrap(), u64::MAX);
+        assert_eq!(parse_bytes("16383PiB").unwrap(), 16383 << 50);
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert!(parse_bytes("18446744073709551616").is_err());
+        assert!(parse_bytes("16384PiB").is_err());
+        assert!(parse_bytes("18446744073709552KB").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for v in ["", "KB", "1.5G", "-1", "10XB", "10 K B", "1K1"] {
+            assert!(parse_bytes(v).is_err(), "{v} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{Arg, ArgValidator};
+
+type TransformFn = Box<dyn Fn(&str) -> String>;
+
+/* Normalizes values before validation so downstream code only sees the canonical form. */
+pub struct ArgTransform {
+    f: TransformFn,
+}
+
+impl ArgTransform {
+    pub fn new(f: impl Fn(&str) -> String + 'static) -> Self {
+        Self { f: Box::new(f) }
+    }
+
+    pub fn trim() -> Self {
+        Self::new(|v| v.trim().to_string())
+    }
+
+    pub fn lowercase() -> Self {
+        Self::new(str::to_lowercase)
+    }
+
+    pub fn expand_home() -> Self {
+        Self::new(expand_home)
+    }
+
+    /* Resolves ~, relative segments and symlinks, a path that does not exist is kept as given. */
+    pub fn canonicalize_path() -> Self {
+        Self::new(|v| {
+            let v = expand_home(v);
+            std::fs::canonicalize(&v)
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or(v)
+        })
+    }
+}
+
+fn expand_home(v: &str) -> String {
+    let rest = match v.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+        _ => return v.to_string(),
+    };
+    match std::env::var("HOME") {
+        Ok(home) => format!("{}{}", home, rest),
+        Err(_) => v.to_string(),
+    }
+}
+
+impl ArgValidator for ArgTransform {
+    fn id(&self) -> Option<String> {
+        Some(String::from("ArgTransform"))
+    }
+
+    fn transform(&self, v: &str) -> String {
+        (self.f)(v)
+    }
+}
+
+impl Arg {
+    pub fn transform(self, f: impl Fn(&str) -> String + 'static) -> Self {
+        self.validate(ArgTransform::new(f))
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use crate::{App, Arg, ArgOptionValidator, ParseError, json, output, paragraph, tui};
+
+/* The --warmup, --repeat and --report keys under the parser's prefixes, empty without a long prefix. */
+fn keys(app: &App) -> [String; 3] {
+    let prefixes = &app.parser().options().prefixes;
+    ["warmup", "repeat", "report"].map(|name| prefixes.long_key(name).unwrap_or_default())
+}
+
+pub fn invoke_bench<T>(mut f: impl FnMut() -> T) -> (T, Duration) {
+    let begin = Instant::now();
+    let res = f();
+    let elapsed = begin.elapsed();
+    (res, elapsed)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bench {
+    name: String,
+    warmups: usize,
+    repetitions: usize,
+}
+
+impl Bench {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            warmups: 0,
+            repetitions: 1,
+        }
+    }
+
+    pub fn warmups(mut self, n: usize) -> Self {
+        self.warmups = n;
+        self
+    }
+
+    pub fn repetitions(mut self, n: usize) -> Self {
+        self.repetitions = n.max(1);
+        self
+    }
+
+    /* Registers --warmup, --repeat and --report on the current tier, nothing without a long prefix. */
+    pub fn add_arguments(app: &mut App) {
+        let [warmup, repeat, report] = keys(app);
+        if warmup.is_empty() {
+            return;
+        }
+        app.add_argument(
+            &warmup,
+            Arg::new()
+                .help("Number of untimed runs before measuring")
+                .require_value()
+                .optional(),
+        );
+        app.add_argument(
+            &repeat,
+            Arg::new()
+                .help("Number of timed runs")
+                .require_value()
+                .optional(),
+        );
+        app.add_argument(
+            &report,
+            Arg::new()
+                .help("Report format")
+                .validate(
+                    ArgOptionValidator::new()
+                        .option("table", Some(String::from("aligned table (default)")))
+                        .option("json", Some(String::from("one JSON document"))),
+                )
+                .optional(),
+        );
+    }
+
+    pub fn from_args(name: impl Into<String>, app: &App) -> Result<Self, ParseError> {
+        let [warmup, repeat, _] = keys(app);
+        let count = |key: &str, default: usize| match app.args().first_of(key) {
+            None => Ok(default),
+            Some(v) => v.parse::<usize>().map_err(|_| {
+                ParseError::invalid_value(format_args!("{} is not a count", v)).key(key)
+            }),
+        };
+        Ok(Self::new(name)
+            .warmups(count(&warmup, 0)?)
+            .repetitions(count(&repeat, 1)?))
+    }
+
+    pub fn run<T>(&self, mut f: impl FnMut() -> T) -> BenchSummary {
+        for _ in 0..self.warmups {
+            std::hint::black_box(f());
+        }
+        let samples = (0..self.repetitions)
+            .map(|_| {
+                let (res, elapsed) = invoke_bench(&mut f);
+                std::hint::black_box(res);
+                elapsed
+            })
+            .collect();
+        BenchSummary::new(self.name.clone(), samples)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchSummary {
+    pub name: String,
+    samples: Vec<Duration>,
+}
+
+impl BenchSummary {
+    pub fn new(name: impl Into<String>, mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        Self {
+            name: name.into(),
+            samples,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.first().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples.last().copied().unwrap_or_default()
+    }
+
+    pub fn avg(&self) -> Duration {
+        match self.samples.len() {
+            0 => Duration::ZERO,
+            n => self.samples.iter().sum::<Duration>() / n as u32,
+        }
+    }
+
+    pub fn percentile(&self, p: f64) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = (p / 100.0 * self.samples.len() as f64).ceil() as usize;
+        self.samples[rank.clamp(1, self.samples.len()) - 1]
+    }
+
+    pub fn p99(&self) -> Duration {
+        self.percentile(99.0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BenchReport {
+    summaries: Vec<BenchSummary>,
+}
+
+impl BenchReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, summary: BenchSummary) -> &mut Self {
+        self.summaries.push(summary);
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BenchSummary> {
+        self.summaries.iter()
+    }
+
+    pub fn table(&self) -> tui::DomNode {
+        let header = ["NAME", "RUNS", "MIN", "AVG", "P99", "MAX"].map(String::from);
+        let rows: Vec<[String; 6]> = self
+            .summaries
+            .iter()
+            .map(|s| {
+                [
+                    s.name.clone(),
+                    s.len().to_string(),
+                    format!("{:?}", s.min()),
+                    format!("{:?}", s.avg()),
+                    format!("{:?}", s.p99()),
+                    format!("{:?}", s.max()),
+                ]
+            })
+            .collect();
+        let widths: Vec<usize> = (0..header.len())
+            .map(|col| {
+                rows.iter()
+                    .chain(std::iter::once(&header))
+                    .map(|row| row[col].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let render = |row: &[String; 6]| {
+            row.iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        let mut layout = tui::Layout::new().append_child(tui::VStack(
+            tui::Layout::new()
+                .style(tui::DomStyle::new().effect(tui::TextEffect::Bold))
+                .append_child(paragraph!("{}", render(&header))),
+        ));
+        for row in rows.iter() {
+            layout = layout.append_child(paragraph!("{}", render(row)));
+        }
+        tui::VStack(layout)
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut buf = String::from("[");
+        for (id, s) in self.summaries.iter().enumerate() {
+            if id > 0 {
+                buf.push(',');
+            }
+            let _ = write!(
+                buf,
+                "{{\"name\":{},\"runs\":{},\"min_ns\":{},\"avg_ns\":{},\"p99_ns\":{},\"max_ns\":{}}}",
+                json::escape(&s.name),
+                s.len(),
+                s.min().as_nanos(),
+                s.avg().as_nanos(),
+                s.p99().as_nanos(),
+                s.max().as_nanos()
+            );
+        }
+        buf.push(']');
+        buf
+    }
+
+    pub fn print(&self, format: BenchFormat) {
+        match format {
+            BenchFormat::Table => output::write(output::Stream::Stdout, &self.table().to_string()),
+            BenchFormat::Json => {
+                output::write(output::Stream::Stdout, &format!("{}\n", self.to_json()))
+            }
+        }
+    }
+
+    pub fn print_with(&self, app: &App) {
+        let [_, _, report] = keys(app);
+        match app.args().first_of(report.as_str()).map(String::as_str) {
+            Some("json") => self.print(BenchFormat::Json),
+            _ => self.print(BenchFormat::Table),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt::Write;
+
+use crate::{App, Arg, ArgKey, ArgValidator, ParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+}
+
+impl TryFrom<&str> for Shell {
+    type Error = ParseError;
+    fn try_from(v: &str) -> Result<Self, ParseError> {
+        match v {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            _ => Err(ParseError::invalid_value(format_args!(
+                "{} is not a supported shell, expected bash or zsh",
+                v
+            ))),
+        }
+    }
+}
+
+impl App {
+    pub fn add_completion_command(&mut self) {
+        self.completion_command = true;
+    }
+
+    pub fn completion_script(&self, shell: Shell) -> String {
+        match shell {
+            Shell::Bash => self.bash_completion(),
+            Shell::Zsh => self.zsh_completion(),
+        }
+    }
+
+    fn completion_params(&self) -> impl Iterator<Item = &(ArgKey, Arg)> {
+        let mut seen: Vec<&ArgKey> = Vec::new();
+        self.parser()
+            .iter()
+            .flat_map(|tier| tier.params_iter())
+            .filter(move |(key, _)| match seen.contains(&key) {
+                true => false,
+                false => {
+                    seen.push(key);
+                    true
+                }
+            })
+    }
+
+    fn bash_completion(&self) -> String {
+        let program = self.program_name();
+        let func = completion_function(program);
+        let mut words: Vec<String> = self
+            .completion_params()
+            .flat_map(|(key, arg)| {
+                std::iter::once(key.value.to_string()).chain(arg.aliases().iter().cloned())
+            })
+            .collect();
+        for tier in self.parser().iter().skip(1) {
+            words.extend(ArgValidator::choices(&tier.pos).unwrap_or_default());
+        }
+        let mut buf = String::new();
+        let _ = writeln!(buf, "{}() {{", func);
+        let _ = writeln!(buf, "    local cur prev");
+        let _ = writeln!(buf, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"");
+        let _ = writeln!(buf, "    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"");
+        let choices: Vec<(&ArgKey, String)> = self
+            .completion_params()
+            .filter_map(|(key, arg)| {
+                ArgValidator::choices(arg).map(|choices| (key, choices.join(" ")))
+            })
+            .collect();
+        if !choices.is_empty() {
+            let _ = writeln!(buf, "    case \"$prev\" in");
+            for (key, words) in choices.iter() {
+                let _ = writeln!(
+                    buf,
+                    "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return 0;;",
+                    key, words
+                );
+            }
+            let _ = writeln!(buf, "    esac");
+            let _ = writeln!(buf, "    case \"$cur\" in");
+            for (key, words) in choices.iter() {
+                let _ = writeln!(
+                    buf,
+                    "        {k}=*) COMPREPLY=($(compgen -P \"{k}=\" -W \"{w}\" -- \"${{cur#{k}=}}\")); return 0;;",
+                    k = key,
+                    w = words
+                );
+            }
+            let _ = writeln!(buf, "    esac");
+        }
+        let _ = writeln!(
+            buf,
+            "    COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+            words.join(" ")
+        );
+        let _ = writeln!(buf, "}}");
+        let _ = writeln!(buf, "complete -F {} {}", func, program);
+        buf
+    }
+
+    fn zsh_completion(&self) -> String {
+        let program = self.program_name();
+        let func = completion_function(program);
+        let mut specs: Vec<String> = self
+            .completion_params()
+            .map(|(key, arg)| {
+                let help = zsh_escape(arg.help_text().unwrap_or_default());
+                let value = match (ArgValidator::allows_empty(arg), ArgValidator::choices(arg)) {
+                    (Some(true), _) => String::new(),
+                    (_, Some(choices)) => format!(":value:({})", choices.join(" ")),
+                    (_, None) => String::from(":value:"),
+                };
+                format!("'{}[{}]{}'", key, help, value)
+            })
+            .collect();
+        for (idx, tier) in self.parser().iter().enumerate().skip(1) {
+            let choices = ArgValidator::choices(&tier.pos)
+                .map(|choices| format!("({})", choices.join(" ")))
+                .unwrap_or_default();
+            specs.push(format!(
+                "'{}:{}:{}'",
+                idx,
+                zsh_escape(&tier.label(idx)),
+                choices
+            ));
+        }
+        let mut buf = String::new();
+        let _ = writeln!(buf, "#compdef {}", program);
+        let _ = writeln!(buf, "{}() {{", func);
+        let _ = writeln!(buf, "    _arguments \\");
+        for spec in specs.iter() {
+            let _ = writeln!(buf, "        {} \\", spec);
+        }
+        let _ = writeln!(buf, "        '*::arg:'");
+        let _ = writeln!(buf, "}}");
+        let _ = writeln!(buf, "compdef {} {}", func, program);
+        buf
+    }
+}
+
+fn completion_function(program: &str) -> String {
+    let name: String = program
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("_{}_complete", name)
+}
+
+fn zsh_escape(v: &str) -> String {
+    v.lines()
+        .next()
+        .unwrap_or_default()
+        .replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+
+use crate::{ArgKey, ArgKeyPrefixes, ParseError, ValueSource, arg_key::unquote};
+
+#[derive(Debug, Default, Clone)]
+pub struct Profile {
+    pub name: String,
+    pub values: Vec<(String, String)>,
+}
+
+impl Profile {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            values: Vec::new(),
+        }
+    }
+
+    pub fn value(mut self, k: impl Into<String>, v: impl Into<String>) -> Self {
+        self.values.push((k.into(), v.into()));
+        self
+    }
+}
+
+/*
+  INI-like config file. Top level `key = value` pairs are defaults for every run, `[name]`
+  sections are profiles selected with --profile. A key without `=` is a flag.
+*/
+#[derive(Debug, Default, Clone)]
+pub struct ConfigFile {
+    pub values: Vec<(String, String)>,
+    pub profiles: Vec<Profile>,
+}
+
+impl ConfigFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ParseError> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) => Err(ParseError::invalid_value(format_args!(
+                "cannot read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        let mut config = Self::new();
+        let mut profile: Option<usize> = None;
+        for (line_no, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[') {
+                let name = match section.strip_suffix(']').map(str::trim) {
+                    Some(name) if !name.is_empty() => name,
+                    _ => {
+                        return Err(ParseError::invalid_value(format_args!(
+                            "line {}: invalid profile header {}",
+                            line_no + 1,
+                            line
+                        )));
+                    }
+                };
+                profile = Some(match config.profiles.iter().position(|p| p.name == name) {
+                    Some(id) => id,
+                    None => {
+                        config.profiles.push(Profile::new(name));
+                        config.profiles.len() - 1
+                    }
+                });
+                continue;
+            }
+            let (k, v) = match line.split_once('=') {
+                Some((k, v)) => (k.trim(), unquote(v.trim())),
+                None => (line, ""),
+            };
+            let values = match profile {
+                Some(id) => &mut config.profiles[id].values,
+                None => &mut config.values,
+            };
+            values.push((k.to_string(), v.to_string()));
+        }
+        Ok(config)
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    pub fn profiles_iter(&self) -> impl Iterator<Item = &Profile> {
+        self.profiles.iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ValueLayer {
+    pub source: ValueSource,
+    values: Vec<(ArgKey, String)>,
+}
+
+impl ValueLayer {
+    pub fn new(source: ValueSource) -> Self {
+        Self {
+            source,
+            values: Vec::new(),
+        }
+    }
+
+    pub fn from_pairs(
+        source: ValueSource,
+        pairs: &[(String, String)],
+        prefixes: &ArgKeyPrefixes,
+    ) -> Self {
+        pairs.iter().fold(Self::new(source), |layer, (k, v)| {
+            match config_key(k, prefixes) {
+                Some(key) => layer.value(key, v.clone()),
+                None => layer,
+            }
+        })
+    }
+
+    pub fn value(mut self, k: ArgKey, v: impl Into<String>) -> Self {
+        self.values.push((k, v.into()));
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(ArgKey, String)> {
+        self.values.iter()
+    }
+}
+
+pub(crate) fn config_key(k: &str, prefixes: &ArgKeyPrefixes) -> Option<ArgKey> {
+    if prefixes.is_arg_key(k) {
+        return ArgKey::make_with(k, prefixes).ok();
+    }
+    let mut chars = k.chars();
+    let key = match (chars.next(), chars.next()) {
+        (Some(c), None) => prefixes.short_key(c),
+        _ => prefixes.long_key(k),
+    };
+    key.and_then(|key| ArgKey::make_with(&key, prefixes).ok())
+}
@@ -0,0 +1,71 @@
+use crate::{ParseError, ParsedArg};
+
+/* Checks a constraint spanning tiers, run once every tier has been parsed. */
+pub trait CrossValidator {
+    fn validate(&self, args: &ParsedArg) -> Result<(), ParseError>;
+}
+
+impl<F: Fn(&ParsedArg) -> Result<(), ParseError>> CrossValidator for F {
+    fn validate(&self, args: &ParsedArg) -> Result<(), ParseError> {
+        self(args)
+    }
+}
+
+/* Requires lower <= upper (or < when strict) whenever both keys are given, comparing as numbers. */
+#[derive(Debug, Clone)]
+pub struct OrderedArgs {
+    lower: String,
+    upper: String,
+    strict: bool,
+}
+
+impl OrderedArgs {
+    pub fn new(lower: impl Into<String>, upper: impl Into<String>) -> Self {
+        Self {
+            lower: lower.into(),
+            upper: upper.into(),
+            strict: false,
+        }
+    }
+
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+}
+
+impl CrossValidator for OrderedArgs {
+    fn validate(&self, args: &ParsedArg) -> Result<(), ParseError> {
+        let (Some(lower), Some(upper)) = (
+            args.find(self.lower.as_str()),
+            args.find(self.upper.as_str()),
+        ) else {
+            return Ok(());
+        };
+        let number = |key: &str, v: &str| {
+            v.parse::<f64>().map_err(|e| {
+                ParseError::invalid_value(format_args!("{} is not a number", v))
+                    .key(key)
+                    .caused_by(e)
+            })
+        };
+        let (low, high) = (number(&self.lower, lower)?, number(&self.upper, upper)?);
+        let ordered = match self.strict {
+            true => low < high,
+            false => low <= high,
+        };
+        match ordered {
+            true => Ok(()),
+            false => Err(ParseError::invalid_value(format_args!(
+                "{} ({}) must be {} {} ({})",
+                self.lower,
+                lower,
+                if self.strict { "<" } else { "<=" },
+                self.upper,
+                upper
+            ))
+            .key(self.lower.clone())
+            .related_key(self.upper.clone())),
+        }
+    }
+}
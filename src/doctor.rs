@@ -0,0 +1,213 @@
+use std::{net::TcpListener, path::PathBuf, process::Command};
+
+use crate::{App, AppVersion, ParseError, paragraph, tui};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn(String),
+    Fail(String),
+}
+
+impl CheckStatus {
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            Self::Pass => "✔",
+            Self::Warn(_) => "!",
+            Self::Fail(_) => "✘",
+        }
+    }
+
+    fn color(&self) -> tui::RgbColor {
+        match self {
+            Self::Pass => tui::RgbColor::green(),
+            Self::Warn(_) => tui::RgbColor::yellow(),
+            Self::Fail(_) => tui::RgbColor::red(),
+        }
+    }
+}
+
+/* A failing critical check makes the doctor command exit non-zero, others only report. */
+pub struct DoctorCheck {
+    name: String,
+    critical: bool,
+    check: Box<dyn Fn() -> CheckStatus>,
+}
+
+impl DoctorCheck {
+    pub fn new(name: impl Into<String>, check: impl Fn() -> CheckStatus + 'static) -> Self {
+        Self {
+            name: name.into(),
+            critical: true,
+            check: Box::new(check),
+        }
+    }
+
+    pub fn critical(mut self, critical: bool) -> Self {
+        self.critical = critical;
+        self
+    }
+
+    pub fn binary_on_path(binary: impl Into<String>) -> Self {
+        let binary = binary.into();
+        Self::new(format!("{} on PATH", binary), move || {
+            match find_on_path(&binary) {
+                Some(_) => CheckStatus::Pass,
+                None => CheckStatus::Fail(format!("{} was not found on PATH", binary)),
+            }
+        })
+    }
+
+    pub fn port_free(port: u16) -> Self {
+        Self::new(
+            format!("port {} free", port),
+            move || match TcpListener::bind(("127.0.0.1", port)) {
+                Ok(_) => CheckStatus::Pass,
+                Err(e) => CheckStatus::Fail(format!("port {} is not available: {}", port, e)),
+            },
+        )
+    }
+
+    pub fn file_exists(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self::new(format!("{} exists", path.display()), move || {
+            match path.exists() {
+                true => CheckStatus::Pass,
+                false => CheckStatus::Fail(format!("{} does not exist", path.display())),
+            }
+        })
+    }
+
+    pub fn env_set(var: impl Into<String>) -> Self {
+        let var = var.into();
+        Self::new(format!("{} set", var), move || {
+            match std::env::var_os(&var) {
+                Some(v) if !v.is_empty() => CheckStatus::Pass,
+                _ => CheckStatus::Fail(format!("{} is not set", var)),
+            }
+        })
+    }
+
+    /* Runs `program --version` and compares the first x.y.z found in its output. */
+    pub fn tool_version(program: impl Into<String>, min: AppVersion) -> Self {
+        let program = program.into();
+        Self::new(format!("{} >= {}", program, min), move || {
+            let output = match Command::new(&program).arg("--version").output() {
+                Ok(output) => output,
+                Err(e) => return CheckStatus::Fail(format!("cannot run {}: {}", program, e)),
+            };
+            let text = String::from_utf8_lossy(&output.stdout).into_owned()
+                + &String::from_utf8_lossy(&output.stderr);
+            match find_version(&text) {
+                None => CheckStatus::Warn(format!("cannot read the version of {}", program)),
+                Some(v) if v < min => {
+                    CheckStatus::Fail(format!("{} {} is older than {}", program, v, min))
+                }
+                Some(_) => CheckStatus::Pass,
+            }
+        })
+    }
+}
+
+fn find_on_path(binary: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| candidate.is_file())
+}
+
+fn find_version(text: &str) -> Option<AppVersion> {
+    text.split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+        .map(|token| token.trim_start_matches('v'))
+        .find_map(|token| AppVersion::try_from(token).ok())
+}
+
+#[derive(Default)]
+pub struct Doctor {
+    checks: Vec<DoctorCheck>,
+}
+
+impl Doctor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn check(mut self, check: DoctorCheck) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    pub fn run(&self) -> DoctorReport {
+        DoctorReport {
+            results: self
+                .checks
+                .iter()
+                .map(|check| (check.name.clone(), check.critical, (check.check)()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DoctorReport {
+    results: Vec<(String, bool, CheckStatus)>,
+}
+
+impl DoctorReport {
+    pub fn iter(&self) -> impl Iterator<Item = &(String, bool, CheckStatus)> {
+        self.results.iter()
+    }
+
+    pub fn critical_failures(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, critical, status)| *critical && matches!(status, CheckStatus::Fail(_)))
+            .count()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.critical_failures() == 0
+    }
+
+    pub fn node(&self) -> tui::DomNode {
+        let mut layout = tui::Layout::new();
+        for (name, _, status) in self.results.iter() {
+            let line = match status {
+                CheckStatus::Pass => format!("{} {}", status.glyph(), name),
+                CheckStatus::Warn(msg) | CheckStatus::Fail(msg) => {
+                    format!("{} {}: {}", status.glyph(), name, msg)
+                }
+            };
+            layout = layout.append_child(tui::VStack(
+                tui::Layout::new()
+                    .style(tui::DomStyle::new().fg(status.color()))
+                    .append_child(paragraph!("{}", line)),
+            ));
+        }
+        tui::VStack(layout)
+    }
+}
+
+impl App {
+    /* Registers a `doctor` built-in command running the checks and rendering their status. */
+    pub fn add_doctor_command(&mut self, doctor: Doctor) {
+        self.doctor = Some(doctor);
+    }
+
+    pub(crate) fn run_doctor(&self) -> Result<(), ParseError> {
+        let Some(doctor) = &self.doctor else {
+            return Ok(());
+        };
+        let report = doctor.run();
+        self.write_out(&self.render_out(&report.node()));
+        match report.critical_failures() {
+            0 => Ok(()),
+            n => Err(ParseError::invalid_value(format_args!(
+                "{} critical check{} failed",
+                n,
+                if n == 1 { "" } else { "s" }
+            ))
+            .key("doctor")),
+        }
+    }
+}
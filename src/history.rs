@@ -0,0 +1,120 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{ParseError, arg_preprocessor::split_tokens};
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub exit_code: i32,
+    pub args: Vec<String>,
+}
+
+impl HistoryEntry {
+    pub fn new(args: Vec<String>, exit_code: i32) -> Self {
+        Self {
+            timestamp: chrono::Utc::now(),
+            exit_code,
+            args,
+        }
+    }
+
+    pub fn command_line(&self) -> String {
+        self.args
+            .iter()
+            .map(|arg| quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(3, '\t');
+        let timestamp = chrono::DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+        let exit_code = fields.next()?.parse().ok()?;
+        let args = split_tokens(fields.next()?).ok()?;
+        Some(Self {
+            timestamp: timestamp.with_timezone(&chrono::Utc),
+            exit_code,
+            args,
+        })
+    }
+}
+
+/* One invocation per line: rfc3339 timestamp, exit code and the shell quoted arguments. */
+#[derive(Debug, Clone)]
+pub struct HistoryFile {
+    path: PathBuf,
+    redacted: Vec<String>,
+}
+
+impl HistoryFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            redacted: Vec::new(),
+        }
+    }
+
+    /* Masks the values of keys matching the `*` wildcard pattern, like App::redact_keys. */
+    pub fn redact(mut self, key: impl Into<String>) -> Self {
+        self.redacted.push(key.into());
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn redacted_iter(&self) -> impl Iterator<Item = &String> {
+        self.redacted.iter()
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> Result<(), ParseError> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| {
+                writeln!(
+                    file,
+                    "{}\t{}\t{}",
+                    entry.timestamp.to_rfc3339(),
+                    entry.exit_code,
+                    entry.command_line()
+                )
+            })
+            .map_err(|e| {
+                ParseError::invalid_value(format_args!(
+                    "cannot write {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })
+    }
+
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>, ParseError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => Ok(content.lines().filter_map(HistoryEntry::parse).collect()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(ParseError::invalid_value(format_args!(
+                "cannot read {}: {}",
+                self.path.display(),
+                e
+            ))),
+        }
+    }
+}
+
+fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_=./:,@+%*".contains(c));
+    match plain {
+        true => arg.to_string(),
+        false => format!("'{}'", arg.replace('\'', "'\\''")),
+    }
+}
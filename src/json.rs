@@ -0,0 +1,212 @@
+use std::fmt::Write;
+
+pub(crate) fn escape(v: &str) -> String {
+    let mut out = String::with_capacity(v.len() + 2);
+    out.push('"');
+    for c in v.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SyntaxError {
+    pub offset: usize,
+    pub msg: &'static str,
+}
+
+/* Validates a whole JSON document and returns the shape of its top level value. */
+pub(crate) fn check(v: &str) -> Result<crate::JsonShape, SyntaxError> {
+    let mut checker = Checker {
+        bytes: v.as_bytes(),
+        pos: 0,
+        depth: 0,
+    };
+    checker.skip_ws();
+    let shape = checker.value()?;
+    checker.skip_ws();
+    match checker.pos == checker.bytes.len() {
+        true => Ok(shape),
+        false => checker.fail("unexpected trailing characters"),
+    }
+}
+
+struct Checker<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl Checker<'_> {
+    const MAX_DEPTH: usize = 128;
+
+    fn fail<T>(&self, msg: &'static str) -> Result<T, SyntaxError> {
+        Err(SyntaxError {
+            offset: self.pos,
+            msg,
+        })
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: u8, msg: &'static str) -> Result<(), SyntaxError> {
+        match self.peek() == Some(c) {
+            true => {
+                self.pos += 1;
+                Ok(())
+            }
+            false => self.fail(msg),
+        }
+    }
+
+    fn value(&mut self) -> Result<crate::JsonShape, SyntaxError> {
+        use crate::JsonShape;
+        match self.peek() {
+            Some(b'{') => self.nested(b'}', true).map(|_| JsonShape::Object),
+            Some(b'[') => self.nested(b']', false).map(|_| JsonShape::Array),
+            Some(b'"') => self.string().map(|_| JsonShape::String),
+            Some(b'-' | b'0'..=b'9') => self.number().map(|_| JsonShape::Number),
+            Some(b't') => self.literal("true").map(|_| JsonShape::Bool),
+            Some(b'f') => self.literal("false").map(|_| JsonShape::Bool),
+            Some(b'n') => self.literal("null").map(|_| JsonShape::Null),
+            None => self.fail("unexpected end of input"),
+            Some(_) => self.fail("expected a value"),
+        }
+    }
+
+    fn nested(&mut self, close: u8, is_object: bool) -> Result<(), SyntaxError> {
+        if self.depth == Self::MAX_DEPTH {
+            return self.fail("nesting is too deep");
+        }
+        self.depth += 1;
+        self.pos += 1;
+        self.skip_ws();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            self.depth -= 1;
+            return Ok(());
+        }
+        loop {
+            if is_object {
+                if self.peek() != Some(b'"') {
+                    return self.fail("expected a string key");
+                }
+                self.string()?;
+                self.skip_ws();
+                self.eat(b':', "expected ':' after the key")?;
+                self.skip_ws();
+            }
+            self.value()?;
+            self.skip_ws();
+            match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    self.skip_ws();
+                }
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    self.depth -= 1;
+                    return Ok(());
+                }
+                None => return self.fail("unexpected end of input"),
+                Some(_) if is_object => return self.fail("expected ',' or '}'"),
+                Some(_) => return self.fail("expected ',' or ']'"),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<(), SyntaxError> {
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                None => return self.fail("unterminated string"),
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                            self.pos += 1
+                        }
+                        Some(b'u') => {
+                            self.pos += 1;
+                            for _ in 0..4 {
+                                match self.peek() {
+                                    Some(c) if c.is_ascii_hexdigit() => self.pos += 1,
+                                    _ => return self.fail("expected 4 hex digits after \\u"),
+                                }
+                            }
+                        }
+                        _ => return self.fail("invalid escape sequence"),
+                    }
+                }
+                Some(c) if c < 0x20 => return self.fail("control character in string"),
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    fn digits(&mut self) -> Result<(), SyntaxError> {
+        let begin = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        match self.pos > begin {
+            true => Ok(()),
+            false => self.fail("expected a digit"),
+        }
+    }
+
+    fn number(&mut self) -> Result<(), SyntaxError> {
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            _ => self.digits()?,
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.digits()?;
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            self.digits()?;
+        }
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &'static str) -> Result<(), SyntaxError> {
+        match self.bytes[self.pos..].starts_with(word.as_bytes()) {
+            true => {
+                self.pos += word.len();
+                Ok(())
+            }
+            false => self.fail("expected true, false or null"),
+        }
+    }
+}
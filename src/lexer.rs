@@ -0,0 +1,230 @@
+use crate::{ArgKey, ArgKeyPrefixes, arg_key::unquote};
+
+/*
+  One argv entry classified without looking at the registered arguments. Whether a short
+  cluster is several flags or a key with an attached value is up to the parser.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    LongFlag {
+        key: ArgKey,
+        value: Option<String>,
+    },
+    ShortCluster {
+        prefix: String,
+        cluster: String,
+        value: Option<String>,
+    },
+    Value(String),
+    Separator,
+    ResponseFile(String),
+}
+
+impl Token {
+    pub fn lex(token: &str, prefixes: &ArgKeyPrefixes) -> Self {
+        if token == "--" {
+            return Self::Separator;
+        }
+        if prefixes.is_long(token) {
+            let (key, value) = ArgKey::split_value(token);
+            if prefixes.is_long(key)
+                && let Ok(key) = ArgKey::make_with(key, prefixes)
+            {
+                return Self::LongFlag {
+                    key,
+                    value: value.map(String::from),
+                };
+            }
+        }
+        if let Some((prefix, rest)) = prefixes.split_prefix(token) {
+            let mut chars = rest.chars();
+            let first = chars.next().map(char::len_utf8).unwrap_or(0);
+            return match rest[first..].strip_prefix('=') {
+                Some(value) => Self::ShortCluster {
+                    prefix: prefix.to_string(),
+                    cluster: rest[..first].to_string(),
+                    value: Some(unquote(value).to_string()),
+                },
+                None => Self::ShortCluster {
+                    prefix: prefix.to_string(),
+                    cluster: rest.to_string(),
+                    value: None,
+                },
+            };
+        }
+        Self::Value(token.to_string())
+    }
+
+    /* The key of a long flag or of a single character short cluster. */
+    pub fn key(&self) -> Option<ArgKey> {
+        match self {
+            Self::LongFlag { key, .. } => Some(key.clone()),
+            Self::ShortCluster {
+                prefix, cluster, ..
+            } if cluster.chars().count() == 1 => {
+                Some(ArgKey::make_unchecked(&format!("{}{}", prefix, cluster)))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            Self::LongFlag { value, .. } | Self::ShortCluster { value, .. } => value.as_deref(),
+            Self::Value(v) | Self::ResponseFile(v) => Some(v),
+            Self::Separator => None,
+        }
+    }
+
+    pub fn is_key(&self) -> bool {
+        matches!(self, Self::LongFlag { .. } | Self::ShortCluster { .. })
+    }
+}
+
+/* Classifies a whole argv, everything after a `--` separator is a plain value. */
+#[derive(Debug, Clone, Default)]
+pub struct Lexer {
+    prefixes: ArgKeyPrefixes,
+    response_prefix: Option<char>,
+}
+
+impl Lexer {
+    pub fn new(prefixes: ArgKeyPrefixes) -> Self {
+        Self {
+            prefixes,
+            response_prefix: None,
+        }
+    }
+
+    pub fn response_files(mut self, prefix: char) -> Self {
+        self.response_prefix = Some(prefix);
+        self
+    }
+
+    pub fn token(&self, token: &str) -> Token {
+        match self.response_prefix {
+            Some(prefix) if token.len() > prefix.len_utf8() && token.starts_with(prefix) => {
+                Token::ResponseFile(token[prefix.len_utf8()..].to_string())
+            }
+            _ => Token::lex(token, &self.prefixes),
+        }
+    }
+
+    pub fn tokens<I: IntoIterator<Item = S>, S: AsRef<str>>(&self, args: I) -> Vec<Token> {
+        let mut separated = false;
+        args.into_iter()
+            .map(|arg| match separated {
+                true => Token::Value(arg.as_ref().to_string()),
+                false => {
+                    let token = self.token(arg.as_ref());
+                    separated = token == Token::Separator;
+                    token
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lexer, Token};
+    use crate::{ArgKey, ArgKeyPrefixes, ArgKeyStyle};
+
+    fn lex(token: &str) -> Token {
+        Token::lex(token, &ArgKeyPrefixes::default())
+    }
+
+    fn long(key: &str, value: Option<&str>) -> Token {
+        Token::LongFlag {
+            key: ArgKey::make_unchecked(key),
+            value: value.map(String::from),
+        }
+    }
+
+    fn short(cluster: &str, value: Option<&str>) -> Token {
+        Token::ShortCluster {
+            prefix: String::from("-"),
+            cluster: String::from(cluster),
+            value: value.map(String::from),
+        }
+    }
+
+    #[test]
+    fn lexes_long_flags() {
+        assert_eq!(lex("--name"), long("--name", None));
+        assert_eq!(lex("--name=v"), long("--name", Some("v")));
+        assert_eq!(lex("--a=b=c"), long("--a", Some("b=c")));
+        assert_eq!(lex("--name="), long("--name", Some("")));
+        assert_eq!(lex("--name=\"\""), long("--name", Some("")));
+        assert_eq!(lex("--name").value(), None);
+        assert_eq!(
+            lex("--name=v").key(),
+            Some(ArgKey::make_unchecked("--name"))
+        );
+    }
+
+    #[test]
+    fn lexes_short_clusters() {
+        assert_eq!(lex("-v"), short("v", None));
+        assert_eq!(lex("-abc"), short("abc", None));
+        assert_eq!(lex("-p=8080"), short("p", Some("8080")));
+        assert_eq!(lex("-p8080"), short("p8080", None));
+        assert_eq!(lex("-é='x y'"), short("é", Some("x y")));
+        assert_eq!(lex("-v").key(), Some(ArgKey::make_unchecked("-v")));
+        assert_eq!(lex("-abc").key(), None);
+        assert!(lex("-abc").is_key());
+    }
+
+    #[test]
+    fn lexes_values_and_separators() {
+        assert_eq!(lex("--"), Token::Separator);
+        for v in ["value", "", "-", "--=v"] {
+            assert_eq!(lex(v), Token::Value(String::from(v)), "{v:?}");
+            assert!(!lex(v).is_key());
+        }
+        assert_eq!(lex("x").value(), Some("x"));
+        assert_eq!(Token::Separator.value(), None);
+    }
+
+    #[test]
+    fn follows_the_prefixes() {
+        let prefixes = ArgKeyStyle::SingleDash.prefixes();
+        assert_eq!(Token::lex("-name", &prefixes), long("-name", None));
+        assert_eq!(
+            Token::lex("--name", &ArgKeyPrefixes::new().long("/")),
+            Token::Value(String::from("--name"))
+        );
+    }
+
+    #[test]
+    fn values_after_the_separator() {
+        let tokens = Lexer::new(ArgKeyPrefixes::default()).tokens(["-v", "--", "--x", "--"]);
+        assert_eq!(
+            tokens,
+            [
+                short("v", None),
+                Token::Separator,
+                Token::Value(String::from("--x")),
+                Token::Value(String::from("--")),
+            ]
+        );
+    }
+
+    #[test]
+    fn response_files_need_a_name() {
+        let lexer = Lexer::new(ArgKeyPrefixes::default()).response_files('@');
+        assert_eq!(
+            lexer.token("@args.txt"),
+            Token::ResponseFile(String::from("args.txt"))
+        );
+        assert_eq!(lexer.token("@"), Token::Value(String::from("@")));
+        assert_eq!(
+            Lexer::new(ArgKeyPrefixes::default()).token("@args.txt"),
+            Token::Value(String::from("@args.txt"))
+        );
+        assert_eq!(
+            lexer.tokens(["--", "@args.txt"])[1],
+            Token::Value(String::from("@args.txt"))
+        );
+    }
+}
@@ -1,23 +1,74 @@
 pub mod action_builder;
 pub mod app;
+pub mod app_config;
+pub mod app_docs;
+pub mod app_history;
 pub mod app_identity;
+pub mod app_io;
+pub mod app_spec;
 pub mod app_version;
 pub mod arg;
+pub mod arg_bool;
+pub mod arg_combinator;
+#[cfg(feature = "datetime")]
+pub mod arg_datetime;
+pub mod arg_duration;
+pub mod arg_enum;
+pub mod arg_glob;
+pub mod arg_json;
 pub mod arg_key;
+pub mod arg_net;
 pub mod arg_parser;
+pub mod arg_preprocessor;
+pub mod arg_size;
+pub mod arg_transform;
+pub mod bench;
+pub mod completion;
+pub mod config;
+pub mod cross_validator;
+pub mod doctor;
+pub mod history;
+mod json;
+pub mod lexer;
+pub mod output;
 pub mod parse_error;
 pub mod parsed_arg;
+pub mod prompt;
+pub mod seed;
+pub mod session;
+mod suggest;
 pub mod tui;
 
 pub use action_builder::*;
 pub use app::*;
 pub use app_identity::*;
+pub use app_io::*;
 pub use app_version::*;
 pub use arg::*;
+pub use arg_bool::*;
+pub use arg_combinator::*;
+#[cfg(feature = "datetime")]
+pub use arg_datetime::*;
+pub use arg_duration::*;
+pub use arg_enum::*;
+pub use arg_glob::*;
+pub use arg_json::*;
 pub use arg_key::*;
+pub use arg_net::*;
 pub use arg_parser::*;
+pub use arg_preprocessor::*;
+pub use arg_size::*;
+pub use arg_transform::*;
+pub use completion::*;
+pub use config::*;
+pub use cross_validator::*;
+pub use doctor::*;
+pub use history::*;
 pub use parse_error::*;
 pub use parsed_arg::*;
+pub use prompt::*;
+pub use seed::*;
+pub use session::*;
 
 #[cfg(feature = "log")]
 pub mod log;
@@ -1,15 +1,21 @@
 use std::{
-    sync::{Mutex, atomic::AtomicBool},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU32, Ordering},
+    },
     thread::{self, JoinHandle, yield_now},
+    time::{Duration, Instant},
 };
 
 use super::prelude::{Emitter, Error};
+use crate::{output, tui};
 
 #[derive(Default)]
 pub struct StdoutEmitter;
 impl Emitter for StdoutEmitter {
     fn emit(&self, v: String) -> Result<(), Error> {
-        print!("{}", v);
+        output::write(output::Stream::Stdout, &v);
+        output::flush_stream(output::Stream::Stdout);
         Ok(())
     }
 }
@@ -26,7 +32,8 @@ impl Emitter for EmptyEmitter {
 pub struct StderrEmitter;
 impl Emitter for StderrEmitter {
     fn emit(&self, v: String) -> Result<(), Error> {
-        eprint!("{}", v);
+        output::write(output::Stream::Stderr, &v);
+        output::flush_stream(output::Stream::Stderr);
         Ok(())
     }
 }
@@ -57,6 +64,82 @@ impl<W: std::io::Write> Emitter for FileEmitter<W> {
     }
 }
 
+/* Removes ANSI escape sequences before handing the record to the inner emitter */
+pub struct StripAnsiEmitter<E: Emitter> {
+    inner: E,
+}
+
+impl<E: Emitter> StripAnsiEmitter<E> {
+    pub fn new(inner: E) -> Self {
+        Self { inner }
+    }
+}
+
+impl<E: Emitter> Emitter for StripAnsiEmitter<E> {
+    fn emit(&self, v: String) -> Result<(), Error> {
+        self.inner.emit(tui::strip_ansi(&v))
+    }
+}
+
+/* Prepends a colored tag to every line of a record, the color is picked from the tag name */
+pub struct TaggedEmitter<E: Emitter> {
+    prefix: String,
+    tag: String,
+    inner: E,
+}
+
+impl<E: Emitter> TaggedEmitter<E> {
+    pub fn new(tag: impl Into<String>, inner: E) -> Self {
+        let tag = tag.into();
+        let style = tui::DomStyle::new().fg(Self::tag_color(&tag));
+        Self {
+            prefix: Self::render_prefix(&tag, style),
+            tag,
+            inner,
+        }
+    }
+
+    pub fn style(mut self, style: tui::DomStyle) -> Self {
+        self.prefix = Self::render_prefix(&self.tag, style);
+        self
+    }
+
+    fn tag_color(tag: &str) -> tui::RgbColor {
+        const PALETTE: [tui::RgbColor; 6] = [
+            tui::RgbColor::cyan(),
+            tui::RgbColor::magenta(),
+            tui::RgbColor::blue(),
+            tui::RgbColor::green(),
+            tui::RgbColor::yellow(),
+            tui::RgbColor::bright_blue(),
+        ];
+        let hash = tag.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+        PALETTE[(hash % PALETTE.len() as u64) as usize]
+    }
+
+    fn render_prefix(tag: &str, style: tui::DomStyle) -> String {
+        format!(
+            "{} ",
+            tui::Layout::new()
+                .style(style)
+                .append_child(tui::Paragraph::new(format_args!("[{}]", tag)).no_newline())
+        )
+    }
+}
+
+impl<E: Emitter> Emitter for TaggedEmitter<E> {
+    fn emit(&self, v: String) -> Result<(), Error> {
+        let mut buf = String::with_capacity(v.len() + self.prefix.len());
+        for line in v.split_inclusive('\n') {
+            buf.push_str(&self.prefix);
+            buf.push_str(line);
+        }
+        self.inner.emit(buf)
+    }
+}
+
 /* Converts any emitter such that now they will log to a queue before emitting out */
 pub struct ThreadedEmitter {
     sender: std::sync::mpsc::Sender<String>,
@@ -115,3 +198,98 @@ impl Emitter for ThreadedEmitter {
             .map_err(|e| Error::io_error(format_args!("{}\n", e)))
     }
 }
+
+/* Emits to the primary emitter, switching to the fallback after consecutive failures */
+pub struct FailoverEmitter {
+    primary: Box<dyn Emitter>,
+    fallback: Box<dyn Emitter>,
+    threshold: u32,
+    probe_interval: Duration,
+    failures: AtomicU32,
+    last_probe: Mutex<Option<Instant>>,
+}
+
+impl FailoverEmitter {
+    pub fn new(primary: impl Emitter + 'static, fallback: impl Emitter + 'static) -> Self {
+        Self {
+            primary: Box::new(primary),
+            fallback: Box::new(fallback),
+            threshold: 3,
+            probe_interval: Duration::from_secs(30),
+            failures: AtomicU32::new(0),
+            last_probe: Mutex::new(None),
+        }
+    }
+
+    pub fn threshold(mut self, failures: u32) -> Self {
+        self.threshold = failures.max(1);
+        self
+    }
+
+    pub fn probe_interval(mut self, interval: Duration) -> Self {
+        self.probe_interval = interval;
+        self
+    }
+
+    pub fn is_failed_over(&self) -> bool {
+        self.last_probe().is_some()
+    }
+
+    fn last_probe(&self) -> Option<Instant> {
+        match self.last_probe.lock() {
+            Ok(v) => *v,
+            Err(e) => *e.into_inner(),
+        }
+    }
+
+    fn set_last_probe(&self, value: Option<Instant>) {
+        let mut guard = match self.last_probe.lock() {
+            Ok(v) => v,
+            Err(e) => e.into_inner(),
+        };
+        *guard = value;
+    }
+
+    fn emit_on_fallback(&self, v: String, last_probe: Instant) -> Result<(), Error> {
+        if last_probe.elapsed() < self.probe_interval {
+            return self.fallback.emit(v);
+        }
+        match self.primary.emit(v.clone()) {
+            Ok(()) => {
+                self.failures.store(0, Ordering::Release);
+                self.set_last_probe(None);
+                self.primary
+                    .emit(String::from("FailoverEmitter: primary emitter recovered\n"))
+            }
+            Err(_) => {
+                self.set_last_probe(Some(Instant::now()));
+                self.fallback.emit(v)
+            }
+        }
+    }
+}
+
+impl Emitter for FailoverEmitter {
+    fn emit(&self, v: String) -> Result<(), Error> {
+        if let Some(last_probe) = self.last_probe() {
+            return self.emit_on_fallback(v, last_probe);
+        }
+        match self.primary.emit(v.clone()) {
+            Ok(()) => {
+                self.failures.store(0, Ordering::Release);
+                Ok(())
+            }
+            Err(e) => {
+                let failures = self.failures.fetch_add(1, Ordering::AcqRel) + 1;
+                if failures >= self.threshold {
+                    self.set_last_probe(Some(Instant::now()));
+                    self.fallback.emit(format!(
+                        "FailoverEmitter: primary emitter failed {} times, switching to fallback ({})\n",
+                        failures, e
+                    ))?;
+                }
+                self.fallback.emit(v)
+            }
+        }
+    }
+}
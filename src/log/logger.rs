@@ -2,7 +2,11 @@ use super::emitters::StdoutEmitter;
 use super::filters::NoFilter;
 use super::formatters::ColorfulFormatter;
 use super::prelude::{Context, Emitter, Filter, Formatter, Level};
-use std::fmt;
+use std::{cell::Cell, fmt, marker::PhantomData};
+
+thread_local! {
+    static LEVEL_OVERRIDE: Cell<Option<Level>> = const { Cell::new(None) };
+}
 
 pub struct Logger {
     filter: Box<dyn Filter>,
@@ -23,15 +27,33 @@ impl Logger {
         self.emitter = Box::new(emitter);
         self
     }
+    pub fn allow(&self, ctx: &Context<'_>) -> bool {
+        match LEVEL_OVERRIDE.with(Cell::get) {
+            Some(level) => ctx.level >= level,
+            None => self.filter.allow(ctx),
+        }
+    }
     pub fn log(&self, ctx: Context<'_>) {
-        if self.filter.allow(&ctx) {
-            self.formatter
-                .fmt(&ctx)
-                .and_then(|msg| self.emitter.emit(msg))
-                .or_else(|e| StdoutEmitter.emit(format!("{}", e)))
-                .unwrap()
+        if self.allow(&ctx) {
+            self.write(ctx)
         }
     }
+    pub fn log_lazy<T: fmt::Display>(&self, ctx: Context<'_>, message: impl FnOnce() -> T) {
+        if self.allow(&ctx) {
+            let message = message();
+            self.write(Context {
+                message: format_args!("{}", message),
+                ..ctx
+            })
+        }
+    }
+    fn write(&self, ctx: Context<'_>) {
+        self.formatter
+            .fmt(&ctx)
+            .and_then(|msg| self.emitter.emit(msg))
+            .or_else(|e| StdoutEmitter.emit(format!("{}", e)))
+            .unwrap()
+    }
 }
 
 impl Default for Logger {
@@ -54,6 +76,30 @@ pub fn root() -> &'static Logger {
     ROOT_LOG.get_or_init(Logger::default)
 }
 
+/* Overrides the filter of every logger on the current thread until the guard is dropped */
+pub struct LevelGuard {
+    previous: Option<Level>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for LevelGuard {
+    fn drop(&mut self) {
+        LEVEL_OVERRIDE.with(|level| level.set(self.previous));
+    }
+}
+
+pub fn override_level(level: Level) -> LevelGuard {
+    LevelGuard {
+        previous: LEVEL_OVERRIDE.with(|current| current.replace(Some(level))),
+        _not_send: PhantomData,
+    }
+}
+
+pub fn with_level<R>(level: Level, f: impl FnOnce() -> R) -> R {
+    let _guard = override_level(level);
+    f()
+}
+
 #[track_caller]
 pub fn log_with(log: &Logger, level: Level, message: fmt::Arguments<'_>) {
     log.log(Context {
@@ -69,6 +115,24 @@ pub fn log(level: Level, message: fmt::Arguments<'_>) {
     log_with(root(), level, message);
 }
 
+#[track_caller]
+pub fn log_lazy_with<T: fmt::Display>(log: &Logger, level: Level, message: impl FnOnce() -> T) {
+    log.log_lazy(
+        Context {
+            level,
+            location: std::panic::Location::caller(),
+            time: chrono::Utc::now(),
+            message: format_args!(""),
+        },
+        message,
+    );
+}
+
+#[track_caller]
+pub fn log_lazy<T: fmt::Display>(level: Level, message: impl FnOnce() -> T) {
+    log_lazy_with(root(), level, message);
+}
+
 #[track_caller]
 pub fn trace_with(log: &Logger, message: fmt::Arguments<'_>) {
     log_with(log, Level::trace(), message);
@@ -131,6 +195,9 @@ pub fn critical(message: fmt::Arguments<'_>) {
 
 #[macro_export]
 macro_rules! trace {
+    (lazy: $message:expr) => {{
+        $crate::log::log_lazy($crate::log::Level::trace(), $message)
+    }};
     ($($arg:tt)*) => {{
         $crate::log::trace(format_args!($($arg)*))
     }};
@@ -138,6 +205,9 @@ macro_rules! trace {
 
 #[macro_export]
 macro_rules! trace_with {
+    ($log:expr, lazy: $message:expr) => {{
+        $crate::log::log_lazy_with($log, $crate::log::Level::trace(), $message)
+    }};
     ($log:expr, $($arg:tt)*) => {{
         $crate::log::trace_with($log, format_args!($($arg)*))
     }};
@@ -145,6 +215,9 @@ macro_rules! trace_with {
 
 #[macro_export]
 macro_rules! debug {
+    (lazy: $message:expr) => {{
+        $crate::log::log_lazy($crate::log::Level::debug(), $message)
+    }};
     ($($arg:tt)*) => {{
         $crate::log::debug(format_args!($($arg)*))
     }};
@@ -152,6 +225,9 @@ macro_rules! debug {
 
 #[macro_export]
 macro_rules! debug_with {
+    ($log:expr, lazy: $message:expr) => {{
+        $crate::log::log_lazy_with($log, $crate::log::Level::debug(), $message)
+    }};
     ($log:expr, $($arg:tt)*) => {{
         $crate::log::debug_with($log, format_args!($($arg)*))
     }};
@@ -159,6 +235,9 @@ macro_rules! debug_with {
 
 #[macro_export]
 macro_rules! info {
+    (lazy: $message:expr) => {{
+        $crate::log::log_lazy($crate::log::Level::info(), $message)
+    }};
     ($($arg:tt)*) => {{
         $crate::log::info(format_args!($($arg)*))
     }};
@@ -166,6 +245,9 @@ macro_rules! info {
 
 #[macro_export]
 macro_rules! info_with {
+    ($log:expr, lazy: $message:expr) => {{
+        $crate::log::log_lazy_with($log, $crate::log::Level::info(), $message)
+    }};
     ($log:expr, $($arg:tt)*) => {{
         $crate::log::info_with($log, format_args!($($arg)*))
     }};
@@ -173,6 +255,9 @@ macro_rules! info_with {
 
 #[macro_export]
 macro_rules! warn {
+    (lazy: $message:expr) => {{
+        $crate::log::log_lazy($crate::log::Level::warn(), $message)
+    }};
     ($($arg:tt)*) => {{
         $crate::log::warn(format_args!($($arg)*))
     }};
@@ -180,6 +265,9 @@ macro_rules! warn {
 
 #[macro_export]
 macro_rules! warn_with {
+    ($log:expr, lazy: $message:expr) => {{
+        $crate::log::log_lazy_with($log, $crate::log::Level::warn(), $message)
+    }};
     ($log:expr, $($arg:tt)*) => {{
         $crate::log::warn_with($log, format_args!($($arg)*))
     }};
@@ -187,6 +275,9 @@ macro_rules! warn_with {
 
 #[macro_export]
 macro_rules! error {
+    (lazy: $message:expr) => {{
+        $crate::log::log_lazy($crate::log::Level::error(), $message)
+    }};
     ($($arg:tt)*) => {{
         $crate::log::error(format_args!($($arg)*))
     }};
@@ -194,6 +285,9 @@ macro_rules! error {
 
 #[macro_export]
 macro_rules! error_with {
+    ($log:expr, lazy: $message:expr) => {{
+        $crate::log::log_lazy_with($log, $crate::log::Level::error(), $message)
+    }};
     ($log:expr, $($arg:tt)*) => {{
         $crate::log::error_with($log, format_args!($($arg)*))
     }};
@@ -201,6 +295,9 @@ macro_rules! error_with {
 
 #[macro_export]
 macro_rules! critical {
+    (lazy: $message:expr) => {{
+        $crate::log::log_lazy($crate::log::Level::critical(), $message)
+    }};
     ($($arg:tt)*) => {{
         $crate::log::critical(format_args!($($arg)*))
     }};
@@ -208,12 +305,136 @@ macro_rules! critical {
 
 #[macro_export]
 macro_rules! critical_with {
+    ($log:expr, lazy: $message:expr) => {{
+        $crate::log::log_lazy_with($log, $crate::log::Level::critical(), $message)
+    }};
     ($log:expr, $($arg:tt)*) => {{
         $crate::log::critical_with($log, format_args!($($arg)*))
     }};
 }
 
+#[macro_export]
+macro_rules! trace_once {
+    ($($arg:tt)*) => {{
+        static ONCE: ::std::sync::atomic::AtomicBool = ::std::sync::atomic::AtomicBool::new(false);
+        if !ONCE.swap(true, ::std::sync::atomic::Ordering::Relaxed) {
+            $crate::log::trace(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! trace_every {
+    ($n:expr, $($arg:tt)*) => {{
+        static COUNTER: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(0);
+        if COUNTER.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed) % ($n as u64).max(1) == 0 {
+            $crate::log::trace(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! debug_once {
+    ($($arg:tt)*) => {{
+        static ONCE: ::std::sync::atomic::AtomicBool = ::std::sync::atomic::AtomicBool::new(false);
+        if !ONCE.swap(true, ::std::sync::atomic::Ordering::Relaxed) {
+            $crate::log::debug(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! debug_every {
+    ($n:expr, $($arg:tt)*) => {{
+        static COUNTER: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(0);
+        if COUNTER.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed) % ($n as u64).max(1) == 0 {
+            $crate::log::debug(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! info_once {
+    ($($arg:tt)*) => {{
+        static ONCE: ::std::sync::atomic::AtomicBool = ::std::sync::atomic::AtomicBool::new(false);
+        if !ONCE.swap(true, ::std::sync::atomic::Ordering::Relaxed) {
+            $crate::log::info(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! info_every {
+    ($n:expr, $($arg:tt)*) => {{
+        static COUNTER: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(0);
+        if COUNTER.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed) % ($n as u64).max(1) == 0 {
+            $crate::log::info(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! warn_once {
+    ($($arg:tt)*) => {{
+        static ONCE: ::std::sync::atomic::AtomicBool = ::std::sync::atomic::AtomicBool::new(false);
+        if !ONCE.swap(true, ::std::sync::atomic::Ordering::Relaxed) {
+            $crate::log::warn(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! warn_every {
+    ($n:expr, $($arg:tt)*) => {{
+        static COUNTER: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(0);
+        if COUNTER.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed) % ($n as u64).max(1) == 0 {
+            $crate::log::warn(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! error_once {
+    ($($arg:tt)*) => {{
+        static ONCE: ::std::sync::atomic::AtomicBool = ::std::sync::atomic::AtomicBool::new(false);
+        if !ONCE.swap(true, ::std::sync::atomic::Ordering::Relaxed) {
+            $crate::log::error(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! error_every {
+    ($n:expr, $($arg:tt)*) => {{
+        static COUNTER: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(0);
+        if COUNTER.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed) % ($n as u64).max(1) == 0 {
+            $crate::log::error(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! critical_once {
+    ($($arg:tt)*) => {{
+        static ONCE: ::std::sync::atomic::AtomicBool = ::std::sync::atomic::AtomicBool::new(false);
+        if !ONCE.swap(true, ::std::sync::atomic::Ordering::Relaxed) {
+            $crate::log::critical(format_args!($($arg)*))
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! critical_every {
+    ($n:expr, $($arg:tt)*) => {{
+        static COUNTER: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(0);
+        if COUNTER.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed) % ($n as u64).max(1) == 0 {
+            $crate::log::critical(format_args!($($arg)*))
+        }
+    }};
+}
+
 pub use crate::{
-    critical, critical_with, debug, debug_with, error, error_with, info, info_with, trace,
-    trace_with, warn, warn_with,
+    critical, critical_every, critical_once, critical_with, debug, debug_every, debug_once,
+    debug_with, error, error_every, error_once, error_with, info, info_every, info_once, info_with,
+    trace, trace_every, trace_once, trace_with, warn, warn_every, warn_once, warn_with,
 };
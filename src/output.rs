@@ -0,0 +1,110 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    sync::{Mutex, MutexGuard},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/* Auto is line buffered on a terminal and block buffered when piped. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Buffering {
+    #[default]
+    Auto,
+    Line,
+    Block,
+    Unbuffered,
+}
+
+/*
+  Only the policies live here, the bytes go straight into std's own stdout and stderr handles.
+  That keeps output ordered with println! and lets std flush stdout when main returns.
+*/
+static STDOUT: Mutex<Buffering> = Mutex::new(Buffering::Auto);
+static STDERR: Mutex<Buffering> = Mutex::new(Buffering::Unbuffered);
+
+fn lock(stream: Stream) -> MutexGuard<'static, Buffering> {
+    let policy = match stream {
+        Stream::Stdout => &STDOUT,
+        Stream::Stderr => &STDERR,
+    };
+    match policy.lock() {
+        Ok(v) => v,
+        Err(e) => e.into_inner(),
+    }
+}
+
+fn is_terminal(stream: Stream) -> bool {
+    match stream {
+        Stream::Stdout => io::stdout().is_terminal(),
+        Stream::Stderr => io::stderr().is_terminal(),
+    }
+}
+
+fn resolved(stream: Stream) -> Buffering {
+    match (buffering(stream), is_terminal(stream)) {
+        (Buffering::Auto, true) => Buffering::Line,
+        (Buffering::Auto, false) => Buffering::Block,
+        (policy, _) => policy,
+    }
+}
+
+/* Line flushes up to the last newline written, Block leaves flushing to std and flush(). */
+fn write_to(out: &mut dyn Write, text: &str, policy: Buffering) {
+    let _ = match policy {
+        Buffering::Unbuffered => out.write_all(text.as_bytes()).and_then(|()| out.flush()),
+        Buffering::Line => match text.rfind('\n') {
+            Some(at) => {
+                let (lines, rest) = text.as_bytes().split_at(at + 1);
+                out.write_all(lines)
+                    .and_then(|()| out.flush())
+                    .and_then(|()| out.write_all(rest))
+            }
+            None => out.write_all(text.as_bytes()),
+        },
+        Buffering::Auto | Buffering::Block => out.write_all(text.as_bytes()),
+    };
+}
+
+/* Stdout defaults to Auto and stderr to Unbuffered. */
+pub fn set_buffering(stream: Stream, policy: Buffering) {
+    flush_stream(stream);
+    *lock(stream) = policy;
+}
+
+pub fn buffering(stream: Stream) -> Buffering {
+    *lock(stream)
+}
+
+/* Pending stdout is flushed before anything reaches stderr so the two stay in order. */
+pub fn write(stream: Stream, text: &str) {
+    let policy = resolved(stream);
+    match stream {
+        Stream::Stdout => write_to(&mut io::stdout().lock(), text, policy),
+        Stream::Stderr => {
+            flush_stream(Stream::Stdout);
+            write_to(&mut io::stderr().lock(), text, policy);
+        }
+    }
+}
+
+pub fn flush_stream(stream: Stream) {
+    let _ = match stream {
+        Stream::Stdout => io::stdout().flush(),
+        Stream::Stderr => io::stderr().flush(),
+    };
+}
+
+/* Flush point for prompts, progress updates and exits. */
+pub fn flush() {
+    flush_stream(Stream::Stdout);
+    flush_stream(Stream::Stderr);
+}
+
+pub fn exit(code: i32) -> ! {
+    flush();
+    std::process::exit(code);
+}
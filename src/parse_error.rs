@@ -11,7 +11,72 @@ pub enum ParseErrorKind {
     NotRequiredArgument,
     NotArgumentKey,
     TooManyValueGiven,
+    TooFewValueGiven,
     NotPositional,
+    MissingRequiredArgument,
+    ConflictingArgument,
+    AmbiguousArgument,
+    InvalidDefinition,
+}
+
+impl ParseErrorKind {
+    pub const ALL: [ParseErrorKind; 12] = [
+        ParseErrorKind::InvalidValue,
+        ParseErrorKind::DuplicateArgument,
+        ParseErrorKind::NoValueGiven,
+        ParseErrorKind::NotRequiredArgument,
+        ParseErrorKind::NotArgumentKey,
+        ParseErrorKind::TooManyValueGiven,
+        ParseErrorKind::TooFewValueGiven,
+        ParseErrorKind::NotPositional,
+        ParseErrorKind::MissingRequiredArgument,
+        ParseErrorKind::ConflictingArgument,
+        ParseErrorKind::AmbiguousArgument,
+        ParseErrorKind::InvalidDefinition,
+    ];
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ParseErrorKind::InvalidValue => "invalid value",
+            ParseErrorKind::DuplicateArgument => "argument given more than once",
+            ParseErrorKind::NoValueGiven => "missing value",
+            ParseErrorKind::NotRequiredArgument => "unknown argument",
+            ParseErrorKind::NotArgumentKey => "malformed argument key",
+            ParseErrorKind::TooManyValueGiven => "too many values",
+            ParseErrorKind::TooFewValueGiven => "too few values",
+            ParseErrorKind::NotPositional => "unexpected positional argument",
+            ParseErrorKind::MissingRequiredArgument => "missing required argument",
+            ParseErrorKind::ConflictingArgument => "conflicting arguments",
+            ParseErrorKind::AmbiguousArgument => "ambiguous argument",
+            ParseErrorKind::InvalidDefinition => "invalid argument definition",
+        }
+    }
+
+    /* Distinct exit codes per failure class so scripts can tell them apart. */
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ParseErrorKind::InvalidValue => 2,
+            ParseErrorKind::NoValueGiven
+            | ParseErrorKind::TooFewValueGiven
+            | ParseErrorKind::MissingRequiredArgument => 3,
+            ParseErrorKind::DuplicateArgument | ParseErrorKind::TooManyValueGiven => 4,
+            ParseErrorKind::ConflictingArgument => 5,
+            ParseErrorKind::NotRequiredArgument
+            | ParseErrorKind::NotArgumentKey
+            | ParseErrorKind::NotPositional
+            | ParseErrorKind::AmbiguousArgument => 6,
+            ParseErrorKind::InvalidDefinition => 70,
+        }
+    }
+}
+
+/* Rarely set context, boxed so the common error stays small on the Result path. */
+#[derive(Debug, Default)]
+struct ErrorDetails {
+    related_keys: Vec<String>,
+    position: Option<(usize, String)>,
+    suggestions: Vec<String>,
+    source: Option<Box<dyn Error + Send + Sync>>,
 }
 
 #[derive(Debug)]
@@ -19,6 +84,7 @@ pub struct ParseError {
     pub kind: ParseErrorKind,
     pub msg: String,
     pub key: Option<String>,
+    details: Option<Box<ErrorDetails>>,
 }
 
 impl ParseError {
@@ -27,9 +93,40 @@ impl ParseError {
             kind,
             msg: fmt::format(args),
             key: None,
+            details: None,
         }
     }
 
+    fn details_mut(&mut self) -> &mut ErrorDetails {
+        self.details.get_or_insert_with(Box::default)
+    }
+
+    pub fn related_keys(&self) -> &[String] {
+        self.details
+            .as_ref()
+            .map(|d| d.related_keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn index(&self) -> Option<usize> {
+        self.details.as_ref()?.position.as_ref().map(|(id, _)| *id)
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.details
+            .as_ref()?
+            .position
+            .as_ref()
+            .map(|(_, token)| token.as_str())
+    }
+
+    pub fn suggestions(&self) -> &[String] {
+        self.details
+            .as_ref()
+            .map(|d| d.suggestions.as_slice())
+            .unwrap_or_default()
+    }
+
     pub fn invalid_value(args: fmt::Arguments<'_>) -> Self {
         Self::from_args(ParseErrorKind::InvalidValue, args)
     }
@@ -54,22 +151,109 @@ impl ParseError {
         Self::from_args(ParseErrorKind::TooManyValueGiven, args)
     }
 
+    pub fn too_few_value_given(args: fmt::Arguments<'_>) -> Self {
+        Self::from_args(ParseErrorKind::TooFewValueGiven, args)
+    }
+
     pub fn not_positional(args: fmt::Arguments<'_>) -> Self {
         Self::from_args(ParseErrorKind::NotPositional, args)
     }
+
+    pub fn missing_required_argument(args: fmt::Arguments<'_>) -> Self {
+        Self::from_args(ParseErrorKind::MissingRequiredArgument, args)
+    }
+
+    pub fn conflicting_argument(args: fmt::Arguments<'_>) -> Self {
+        Self::from_args(ParseErrorKind::ConflictingArgument, args)
+    }
+
+    pub fn ambiguous_argument(args: fmt::Arguments<'_>) -> Self {
+        Self::from_args(ParseErrorKind::AmbiguousArgument, args)
+    }
+
+    pub fn invalid_definition(args: fmt::Arguments<'_>) -> Self {
+        Self::from_args(ParseErrorKind::InvalidDefinition, args)
+    }
+
     pub fn key(mut self, k: impl Into<String>) -> Self {
         self.key = Some(k.into());
         self
     }
+
+    /* Wraps the underlying failure, e.g. an io or number parse error, exposed through source(). */
+    pub fn caused_by(mut self, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        self.details_mut().source = Some(source.into());
+        self
+    }
+
+    /* Every error in the source() chain, outermost first, excluding this one. */
+    pub fn causes(&self) -> Vec<String> {
+        let mut causes = Vec::new();
+        let mut cur = self.source();
+        while let Some(err) = cur {
+            causes.push(err.to_string());
+            cur = err.source();
+        }
+        causes
+    }
+
+    /* A likely intended value, rendered as a "did you mean" line. */
+    pub fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+        self.details_mut().suggestions.push(suggestion.into());
+        self
+    }
+
+    /* The argv index and text of the offending token. */
+    pub fn at(mut self, index: usize, token: impl Into<String>) -> Self {
+        self.details_mut().position = Some((index, token.into()));
+        self
+    }
+
+    /* The command line with a caret line under the offending token, e.g. `--port abc`. */
+    pub fn snippet(&self, argv: &[String]) -> Option<String> {
+        let index = self.index()?;
+        let target = argv.get(index)?;
+        let offset: usize = argv[..index].iter().map(|v| v.chars().count() + 1).sum();
+        Some(format!(
+            "{}\n{}{}",
+            argv.join(" "),
+            " ".repeat(offset),
+            "^".repeat(target.chars().count().max(1))
+        ))
+    }
+
+    /* Another key involved in the error, e.g. the other side of a cross-tier constraint. */
+    pub fn related_key(mut self, k: impl Into<String>) -> Self {
+        self.details_mut().related_keys.push(k.into());
+        self
+    }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.key {
             None => write!(f, "{:?}: {}", self.kind, self.msg),
-            Some(k) => write!(f, "{}: {:?}({})", k, self.kind, self.msg),
+            Some(k) if self.related_keys().is_empty() => {
+                write!(f, "{}: {:?}({})", k, self.kind, self.msg)
+            }
+            Some(k) => write!(
+                f,
+                "{}, {}: {:?}({})",
+                k,
+                self.related_keys().join(", "),
+                self.kind,
+                self.msg
+            ),
         }
     }
 }
 
-impl Error for ParseError {}
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.details
+            .as_ref()?
+            .source
+            .as_ref()
+            .map(|e| e.as_ref() as &(dyn Error + 'static))
+    }
+}
@@ -1,14 +1,91 @@
-use crate::ArgKey;
+use std::{collections::HashMap, fmt};
 
+use crate::{ArgKey, KeyQuery};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueSource {
+    Cli,
+    Env,
+    Profile,
+    Config,
+    Prompt,
+    Default,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Cli => "cli",
+            Self::Env => "env",
+            Self::Profile => "profile",
+            Self::Config => "config",
+            Self::Prompt => "prompt",
+            Self::Default => "default",
+        })
+    }
+}
+
+/* What a value of iter_all was given for: the positional opening a tier or a keyword. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParsedKey<'a> {
+    Positional,
+    Key(&'a ArgKey),
+}
+
+/* params keeps parse order, index maps each key to its positions in params. */
 #[derive(Debug)]
 struct ParamTier {
     value: String,
+    secret: bool,
     params: Vec<(ArgKey, String)>,
+    sources: Vec<ValueSource>,
+    index: HashMap<ArgKey, Vec<usize>>,
+}
+
+impl ParamTier {
+    fn new(value: String) -> Self {
+        Self {
+            value,
+            secret: false,
+            params: Vec::new(),
+            sources: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+    fn push(&mut self, key: ArgKey, value: String, source: ValueSource) {
+        self.index
+            .entry(key.clone())
+            .or_default()
+            .push(self.params.len());
+        self.params.push((key, value));
+        self.sources.push(source);
+    }
+    fn positions(&self, key: &(impl KeyQuery + ?Sized)) -> &[usize] {
+        self.index
+            .get(key.key_str())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+    fn values_of<'a>(&'a self, key: &(impl KeyQuery + ?Sized)) -> impl Iterator<Item = &'a str> {
+        self.positions(key)
+            .iter()
+            .map(|id| self.params[*id].1.as_str())
+    }
+    /* Rebuilds the index after params were removed or reordered. */
+    fn reindex(&mut self) {
+        self.index.clear();
+        for (id, (key, _)) in self.params.iter().enumerate() {
+            self.index.entry(key.clone()).or_default().push(id);
+        }
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct ParsedArg {
     values: Vec<ParamTier>,
+    warnings: Vec<String>,
+    raw: Vec<(ArgKey, Vec<String>)>,
+    secrets: Vec<ArgKey>,
 }
 impl ParsedArg {
     // Modification Functions
@@ -16,26 +93,155 @@ impl ParsedArg {
         Self::default()
     }
     pub fn add_positional_argument(&mut self, v: impl Into<String>) -> &mut Self {
-        self.values.push(ParamTier {
-            value: v.into(),
-            params: Vec::new(),
-        });
+        self.values.push(ParamTier::new(v.into()));
         self
     }
     pub fn add_argument(&mut self, k: impl Into<ArgKey>, v: impl Into<String>) -> &mut Self {
+        self.add_argument_from(k, v, ValueSource::Cli)
+    }
+    pub fn add_argument_from(
+        &mut self,
+        k: impl Into<ArgKey>,
+        v: impl Into<String>,
+        source: ValueSource,
+    ) -> &mut Self {
         self.values
             .last_mut()
             .unwrap()
-            .params
-            .push((k.into(), v.into()));
+            .push(k.into(), v.into(), source);
+        self
+    }
+    /* Swaps every value of key in the current tier for values, keeping the first one's place and source. */
+    pub fn replace_values(
+        &mut self,
+        k: &(impl KeyQuery + ?Sized),
+        values: impl IntoIterator<Item = String>,
+    ) -> &mut Self {
+        let tier = self.values.last_mut().unwrap();
+        let Some(&at) = tier.positions(k).first() else {
+            return self;
+        };
+        let (key, source) = (tier.params[at].0.clone(), tier.sources[at]);
+        let mut values: Vec<(ArgKey, String)> =
+            values.into_iter().map(|v| (key.clone(), v)).collect();
+        let mut params = Vec::with_capacity(tier.params.len() + values.len());
+        let mut sources = Vec::with_capacity(params.capacity());
+        for (id, (param, param_source)) in std::mem::take(&mut tier.params)
+            .into_iter()
+            .zip(std::mem::take(&mut tier.sources))
+            .enumerate()
+        {
+            if id == at {
+                sources.extend(std::iter::repeat_n(source, values.len()));
+                params.append(&mut values);
+            } else if !(k == &param.0) {
+                params.push(param);
+                sources.push(param_source);
+            }
+        }
+        tier.params = params;
+        tier.sources = sources;
+        tier.reindex();
+        self
+    }
+    /* Moves the first value of key in the current tier out instead of cloning it. */
+    pub fn take_first_of(&mut self, k: &(impl KeyQuery + ?Sized)) -> Option<String> {
+        let tier = self.values.last_mut()?;
+        let at = *tier.positions(k).first()?;
+        tier.sources.remove(at);
+        let (_, value) = tier.params.remove(at);
+        tier.reindex();
+        Some(value)
+    }
+    pub fn take_values_of(&mut self, k: &(impl KeyQuery + ?Sized)) -> Vec<String> {
+        let Some(tier) = self.values.last_mut() else {
+            return Vec::new();
+        };
+        if tier.positions(k).is_empty() {
+            return Vec::new();
+        }
+        let mut taken = Vec::new();
+        let mut id = 0;
+        while id < tier.params.len() {
+            if k == &tier.params[id].0 {
+                tier.sources.remove(id);
+                taken.push(tier.params.remove(id).1);
+            } else {
+                id += 1;
+            }
+        }
+        tier.reindex();
+        taken
+    }
+    pub fn add_raw(&mut self, k: impl Into<ArgKey>, raw: Vec<String>) -> &mut Self {
+        self.raw.push((k.into(), raw));
         self
     }
+    /* A warning already pending is not added again, e.g. when a tier is validated twice. */
+    pub fn add_warning(&mut self, msg: impl Into<String>) -> &mut Self {
+        let msg = msg.into();
+        if !self.warnings.contains(&msg) {
+            self.warnings.push(msg);
+        }
+        self
+    }
+    /* Keys of secret() arguments, masked when serialized. */
+    pub(crate) fn mark_secret(&mut self, key: &ArgKey) {
+        if !self.secrets.contains(key) {
+            self.secrets.push(key.clone());
+        }
+    }
+    pub(crate) fn mark_secret_positional(&mut self) {
+        if let Some(tier) = self.values.last_mut() {
+            tier.secret = true;
+        }
+    }
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
     pub fn arg(&self) -> &str {
         &self.values.last().unwrap().value
     }
+    pub fn arg_at(&self, tier: usize) -> Option<&str> {
+        self.values.get(tier).map(|tier| tier.value.as_str())
+    }
+    pub fn tier(&self, index: usize) -> Option<TierView<'_>> {
+        self.values.get(index).map(|tier| TierView { index, tier })
+    }
+    pub fn tiers(&self) -> impl Iterator<Item = TierView<'_>> {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(index, tier)| TierView { index, tier })
+    }
     pub fn param_iter(&self) -> impl Iterator<Item = &(ArgKey, String)> {
         self.values.last().unwrap().params.iter()
     }
+    pub fn all_param_iter(&self) -> impl Iterator<Item = &(ArgKey, String)> {
+        self.values.iter().flat_map(|tier| tier.params.iter())
+    }
+    /* Every value as (tier, key, value) in parse order, each tier's positional before its params. */
+    pub fn iter_all(&self) -> impl Iterator<Item = (usize, ParsedKey<'_>, &str)> {
+        self.values.iter().enumerate().flat_map(|(index, tier)| {
+            std::iter::once((index, ParsedKey::Positional, tier.value.as_str())).chain(
+                tier.params
+                    .iter()
+                    .map(move |(key, v)| (index, ParsedKey::Key(key), v.as_str())),
+            )
+        })
+    }
+    /* Every keyword value as (tier, key, value, source), for explaining effective settings. */
+    pub fn provenance(&self) -> impl Iterator<Item = (usize, &ArgKey, &str, ValueSource)> {
+        self.values.iter().enumerate().flat_map(|(index, tier)| {
+            tier.params
+                .iter()
+                .zip(tier.sources.iter())
+                .map(move |((key, v), source)| (index, key, v.as_str(), *source))
+        })
+    }
     pub fn len(&self) -> usize {
         self.values.len()
     }
@@ -44,24 +250,220 @@ impl ParsedArg {
     }
 
     // Query Function
-    pub fn first_of(&self, k: &(impl PartialEq<ArgKey> + ?Sized)) -> Option<&String> {
-        match self.param_iter().find(|&(param_key, _)| k == param_key) {
-            None => None,
-            Some((_, v)) => Some(v),
-        }
+    pub fn first_of(&self, k: &(impl KeyQuery + ?Sized)) -> Option<&String> {
+        let tier = self.values.last()?;
+        tier.positions(k).first().map(|id| &tier.params[*id].1)
+    }
+    /* Looks the key up in every tier, the deepest tier that has it wins. */
+    pub fn find(&self, k: &(impl KeyQuery + ?Sized)) -> Option<&String> {
+        self.values
+            .iter()
+            .rev()
+            .find_map(|tier| tier.positions(k).first().map(|id| &tier.params[*id].1))
     }
     pub fn filter<'a>(
         &'a self,
-        key: &(impl PartialEq<ArgKey> + ?Sized),
+        key: &(impl KeyQuery + ?Sized),
     ) -> impl Iterator<Item = &'a String> {
-        self.param_iter()
-            .filter(move |&arg| key == &arg.0)
-            .map(move |arg| &arg.1)
+        let tier = self.values.last().unwrap();
+        tier.positions(key).iter().map(|id| &tier.params[*id].1)
+    }
+    pub fn count(&self, key: &(impl KeyQuery + ?Sized)) -> usize {
+        self.values
+            .last()
+            .map_or(0, |tier| tier.positions(key).len())
+    }
+    pub fn source_of(&self, key: &(impl KeyQuery + ?Sized)) -> Option<ValueSource> {
+        let tier = self.values.last()?;
+        tier.positions(key).first().map(|id| tier.sources[*id])
+    }
+    pub fn raw_after(&self, key: &(impl KeyQuery + ?Sized)) -> Option<&[String]> {
+        self.raw
+            .iter()
+            .find(|(raw_key, _)| key == raw_key)
+            .map(|(_, raw)| raw.as_slice())
     }
-    pub fn count(&self, key: &(impl PartialEq<ArgKey> + ?Sized)) -> usize {
-        self.filter(key).count()
+    /* The captured tokens as a command, arguments are passed as-is without a shell. */
+    pub fn command_after(&self, key: &(impl KeyQuery + ?Sized)) -> Option<std::process::Command> {
+        let (program, args) = self.raw_after(key)?.split_first()?;
+        let mut command = std::process::Command::new(program);
+        command.args(args);
+        Some(command)
     }
-    pub fn contains(&self, key: &(impl PartialEq<ArgKey> + ?Sized)) -> bool {
+    /* Every value of key from all tiers, outermost tier first and in parse order within a tier. */
+    pub fn values_of(&self, key: &(impl KeyQuery + ?Sized)) -> Vec<&str> {
+        self.values
+            .iter()
+            .flat_map(|tier| tier.values_of(key))
+            .collect()
+    }
+    /* The value given last, which is the one that wins for a repeated single-value option. */
+    pub fn last_of(&self, key: &(impl KeyQuery + ?Sized)) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find_map(|tier| tier.values_of(key).last())
+    }
+    /* How many times key was typed on the command line in any tier, so -v -v -v counts 3 and defaults count 0. */
+    pub fn occurrences(&self, key: &(impl KeyQuery + ?Sized)) -> usize {
+        self.values
+            .iter()
+            .flat_map(|tier| {
+                tier.positions(key)
+                    .iter()
+                    .filter(|id| tier.sources[**id] == ValueSource::Cli)
+            })
+            .count()
+    }
+    pub fn contains(&self, key: &(impl KeyQuery + ?Sized)) -> bool {
+        self.first_of(key).is_some()
+    }
+    /* Whether key got a value in any tier from somewhere other than its default. */
+    pub fn is_given(&self, key: &(impl KeyQuery + ?Sized)) -> bool {
+        self.values.iter().any(|tier| {
+            tier.positions(key)
+                .iter()
+                .any(|id| tier.sources[*id] != ValueSource::Default)
+        })
+    }
+}
+
+/* Read-only view of one tier: the positional value that opened it and the parameters parsed in it. */
+#[derive(Debug, Clone, Copy)]
+pub struct TierView<'a> {
+    index: usize,
+    tier: &'a ParamTier,
+}
+
+impl<'a> TierView<'a> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+    pub fn value(&self) -> &'a str {
+        &self.tier.value
+    }
+    pub fn param_iter(&self) -> impl Iterator<Item = &'a (ArgKey, String)> + use<'a> {
+        self.tier.params.iter()
+    }
+    pub fn len(&self) -> usize {
+        self.tier.params.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.tier.params.is_empty()
+    }
+    pub fn first_of(&self, key: &(impl KeyQuery + ?Sized)) -> Option<&'a str> {
+        self.values_of(key).next()
+    }
+    pub fn values_of(&self, key: &(impl KeyQuery + ?Sized)) -> impl Iterator<Item = &'a str> {
+        self.tier.values_of(key)
+    }
+    pub fn count(&self, key: &(impl KeyQuery + ?Sized)) -> usize {
+        self.values_of(key).count()
+    }
+    pub fn contains(&self, key: &(impl KeyQuery + ?Sized)) -> bool {
         self.first_of(key).is_some()
     }
+    pub fn source_of(&self, key: &(impl KeyQuery + ?Sized)) -> Option<ValueSource> {
+        self.tier
+            .positions(key)
+            .first()
+            .map(|id| self.tier.sources[*id])
+    }
+}
+
+/* {"tiers": [{"value", "params": [{"key", "value", "source"}]}], "raw": [{"key", "values"}], "warnings"} */
+#[cfg(feature = "serde")]
+mod serialize {
+    use serde::ser::{Serialize, SerializeMap, SerializeSeq, SerializeStruct, Serializer};
+
+    use super::{ParamTier, ParsedArg, ValueSource};
+
+    const MASK: &str = "***";
+
+    impl Serialize for ValueSource {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    struct Params<'a>(&'a ParamTier, &'a [crate::ArgKey]);
+
+    impl Serialize for Params<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.0.params.len()))?;
+            for ((key, value), source) in self.0.params.iter().zip(self.0.sources.iter()) {
+                let value = match self.1.contains(key) {
+                    true => MASK,
+                    false => value,
+                };
+                seq.serialize_element(&Param { key, value, source })?;
+            }
+            seq.end()
+        }
+    }
+
+    struct Param<'a> {
+        key: &'a crate::ArgKey,
+        value: &'a str,
+        source: &'a ValueSource,
+    }
+
+    impl Serialize for Param<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(3))?;
+            map.serialize_entry("key", self.key)?;
+            map.serialize_entry("value", self.value)?;
+            map.serialize_entry("source", self.source)?;
+            map.end()
+        }
+    }
+
+    struct Tier<'a>(&'a ParamTier, &'a [crate::ArgKey]);
+
+    impl Serialize for Tier<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let value = match self.0.secret {
+                true => MASK,
+                false => &self.0.value,
+            };
+            let mut state = serializer.serialize_struct("Tier", 2)?;
+            state.serialize_field("value", value)?;
+            state.serialize_field("params", &Params(self.0, self.1))?;
+            state.end()
+        }
+    }
+
+    struct Raw<'a>(&'a crate::ArgKey, &'a [String], bool);
+
+    impl Serialize for Raw<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(2))?;
+            map.serialize_entry("key", self.0)?;
+            match self.2 {
+                true => map.serialize_entry("values", &vec![MASK; self.1.len()])?,
+                false => map.serialize_entry("values", self.1)?,
+            }
+            map.end()
+        }
+    }
+
+    impl Serialize for ParsedArg {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let raw: Vec<Raw<'_>> = self
+                .raw
+                .iter()
+                .map(|(k, v)| Raw(k, v, self.secrets.contains(k)))
+                .collect();
+            let tiers: Vec<Tier<'_>> = self
+                .values
+                .iter()
+                .map(|tier| Tier(tier, &self.secrets))
+                .collect();
+            let mut state = serializer.serialize_struct("ParsedArg", 3)?;
+            state.serialize_field("tiers", &tiers)?;
+            state.serialize_field("raw", &raw)?;
+            state.serialize_field("warnings", &self.warnings)?;
+            state.end()
+        }
+    }
 }
@@ -0,0 +1,533 @@
+use std::{
+    fmt,
+    io::{self, BufRead, IsTerminal, Write},
+    sync::{Mutex, mpsc},
+    time::Duration,
+};
+
+#[cfg(windows)]
+use crate::tui::input::console;
+#[cfg(unix)]
+use crate::tui::input::stty;
+use crate::{App, Arg, ArgValidator, ParseError, Validation, output, tui};
+
+/* What a prompt does when nobody can answer it: stdin is not a TTY, CI=true or it timed out. */
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum NonInteractive {
+    #[default]
+    UseDefault,
+    Fail,
+    Env(String),
+}
+
+impl NonInteractive {
+    pub fn resolve(&self, default: Option<&str>) -> Result<String, ParseError> {
+        match self {
+            Self::UseDefault => default.map(String::from).ok_or_else(|| {
+                ParseError::no_value_given(format_args!(
+                    "no default answer available without an interactive terminal"
+                ))
+            }),
+            Self::Fail => Err(ParseError::no_value_given(format_args!(
+                "an interactive terminal is required"
+            ))),
+            Self::Env(var) => std::env::var(var).map_err(|_| {
+                ParseError::no_value_given(format_args!(
+                    "{} is not set and no interactive terminal is available",
+                    var
+                ))
+            }),
+        }
+    }
+}
+
+pub fn is_ci() -> bool {
+    std::env::var("CI").is_ok_and(|v| v.eq_ignore_ascii_case("true") || v == "1")
+}
+
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && !is_ci()
+}
+
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    message: String,
+    default: Option<String>,
+    policy: NonInteractive,
+    timeout: Option<Duration>,
+}
+
+impl Prompt {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            default: None,
+            policy: NonInteractive::default(),
+            timeout: None,
+        }
+    }
+
+    pub fn default(mut self, value: impl Into<String>) -> Self {
+        self.default = Some(value.into());
+        self
+    }
+
+    pub fn non_interactive(mut self, policy: NonInteractive) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn ask(&self) -> Result<String, ParseError> {
+        if !is_interactive() {
+            return self.policy.resolve(self.default.as_deref());
+        }
+        output::flush();
+        let mut stderr = io::stderr();
+        let _ = match &self.default {
+            Some(v) => write!(stderr, "{} [{}]: ", self.message, v),
+            None => write!(stderr, "{}: ", self.message),
+        };
+        let _ = stderr.flush();
+        let line = match self.timeout {
+            None => read_line(),
+            Some(timeout) => match read_line_timeout(timeout) {
+                Some(line) => line,
+                None => {
+                    let _ = writeln!(stderr);
+                    return self.policy.resolve(self.default.as_deref());
+                }
+            },
+        }?;
+        Ok(self.answer(line))
+    }
+
+    /*
+      Asks until the validator accepts the transformed answer, printing why a value was refused.
+      Without a terminal the policy's answer is checked once.
+    */
+    pub fn ask_validated(&self, validator: &dyn ArgValidator) -> Result<String, ParseError> {
+        let (value, validation) = self.ask_checked(validator)?;
+        if let Validation::Warn(msg) = validation {
+            let _ = writeln!(io::stderr(), "{}", msg);
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn ask_checked(
+        &self,
+        validator: &dyn ArgValidator,
+    ) -> Result<(String, Validation), ParseError> {
+        ask_until_valid(validator, || self.ask(), report_refused)
+    }
+
+    /* Asks through the given reader and writer, treated as interactive and without a timeout. */
+    pub fn ask_with(
+        &self,
+        reader: &mut dyn BufRead,
+        writer: &mut dyn Write,
+    ) -> Result<String, ParseError> {
+        let _ = match &self.default {
+            Some(v) => write!(writer, "{} [{}]: ", self.message, v),
+            None => write!(writer, "{}: ", self.message),
+        };
+        let _ = writer.flush();
+        read_line_from(reader).map(|line| self.answer(line))
+    }
+
+    fn answer(&self, line: String) -> String {
+        match (line.is_empty(), &self.default) {
+            (true, Some(v)) => v.clone(),
+            _ => line,
+        }
+    }
+}
+
+/*
+  Asks until the validator accepts the transformed answer. refused is told why a value was not
+  accepted and decides whether to ask again.
+*/
+pub(crate) fn ask_until_valid(
+    validator: &dyn ArgValidator,
+    mut ask: impl FnMut() -> Result<String, ParseError>,
+    mut refused: impl FnMut(&ParseError) -> bool,
+) -> Result<(String, Validation), ParseError> {
+    loop {
+        let value = validator.transform(&ask()?);
+        match validator.check_value(Some(&value)) {
+            Ok(validation) => return Ok((value, validation)),
+            Err(e) if refused(&e) => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/* Prints why a value was refused on the terminal, nobody can answer again without one. */
+pub(crate) fn report_refused(e: &ParseError) -> bool {
+    if !is_interactive() {
+        return false;
+    }
+    let _ = writeln!(io::stderr(), "{}", e.msg);
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmDefault {
+    Yes,
+    No,
+}
+
+impl ConfirmDefault {
+    fn answer(self) -> bool {
+        self == Self::Yes
+    }
+}
+
+/*
+  Asks a y/n question on stderr. An empty answer takes the default and anything else asks again.
+  Without an interactive terminal the default is the answer.
+*/
+pub fn confirm(message: &str, default: ConfirmDefault) -> Result<bool, ParseError> {
+    if !is_interactive() {
+        return Ok(default.answer());
+    }
+    output::flush();
+    let color = io::stderr().is_terminal();
+    ask_confirm(
+        message,
+        default,
+        &mut io::stdin().lock(),
+        &mut io::stderr(),
+        color,
+    )
+}
+
+/* Asks through the given reader and writer, treated as interactive and without color. */
+pub fn confirm_with(
+    message: &str,
+    default: ConfirmDefault,
+    reader: &mut dyn BufRead,
+    writer: &mut dyn Write,
+) -> Result<bool, ParseError> {
+    ask_confirm(message, default, reader, writer, false)
+}
+
+fn ask_confirm(
+    message: &str,
+    default: ConfirmDefault,
+    reader: &mut dyn BufRead,
+    writer: &mut dyn Write,
+    color: bool,
+) -> Result<bool, ParseError> {
+    let choices = match default {
+        ConfirmDefault::Yes => "[Y/n]",
+        ConfirmDefault::No => "[y/N]",
+    };
+    let question = tui::Layout::new()
+        .append_child(
+            tui::Layout::new()
+                .style(tui::DomStyle::new().effect(tui::TextEffect::Bold))
+                .append_child(tui::Paragraph::new(format_args!("{} ", message)).no_newline()),
+        )
+        .append_child(
+            tui::Layout::new()
+                .style(tui::DomStyle::new().effect(tui::TextEffect::Dim))
+                .append_child(tui::Paragraph::new(format_args!("{} ", choices)).no_newline()),
+        );
+    loop {
+        let _ = write!(
+            writer,
+            "{}",
+            tui::DomNode::from(question.clone()).render(color)
+        );
+        let _ = writer.flush();
+        let line = read_line_from(reader)?;
+        match line.trim().to_lowercase().as_str() {
+            "" => return Ok(default.answer()),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => {
+                let _ = writeln!(writer, "Please answer y or n.");
+            }
+        }
+    }
+}
+
+/* Text that must not end up in logs, Debug prints a placeholder instead of the value. */
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString(***)")
+    }
+}
+
+/* Reads a line with terminal echo turned off, optionally asking a second time to confirm it. */
+#[derive(Debug, Clone)]
+pub struct Password {
+    message: String,
+    confirm: Option<String>,
+    policy: NonInteractive,
+}
+
+impl Password {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            confirm: None,
+            policy: NonInteractive::Fail,
+        }
+    }
+
+    /* Asks again with this message and starts over when the two answers differ. */
+    pub fn confirm(mut self, message: impl Into<String>) -> Self {
+        self.confirm = Some(message.into());
+        self
+    }
+
+    pub fn non_interactive(mut self, policy: NonInteractive) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn ask(&self) -> Result<SecretString, ParseError> {
+        if !is_interactive() {
+            return self.policy.resolve(None).map(SecretString);
+        }
+        output::flush();
+        self.ask_twice(&mut io::stderr(), read_hidden)
+    }
+
+    /* Asks through the given reader and writer, treated as interactive. */
+    pub fn ask_with(
+        &self,
+        reader: &mut dyn BufRead,
+        writer: &mut dyn Write,
+    ) -> Result<SecretString, ParseError> {
+        self.ask_twice(writer, |message, writer| {
+            let _ = write!(writer, "{}", message);
+            let _ = writer.flush();
+            read_line_from(reader)
+        })
+    }
+
+    fn ask_twice(
+        &self,
+        writer: &mut dyn Write,
+        mut read: impl FnMut(&str, &mut dyn Write) -> Result<String, ParseError>,
+    ) -> Result<SecretString, ParseError> {
+        loop {
+            let first = read(&self.message, writer)?;
+            let Some(confirm) = &self.confirm else {
+                return Ok(SecretString(first));
+            };
+            if read(confirm, writer)? == first {
+                return Ok(SecretString(first));
+            }
+            let _ = writeln!(writer, "The values did not match, try again.");
+        }
+    }
+}
+
+pub fn password(message: impl Into<String>) -> Result<SecretString, ParseError> {
+    Password::new(message).ask()
+}
+
+fn read_hidden(message: &str, writer: &mut dyn Write) -> Result<String, ParseError> {
+    let _ = write!(writer, "{}", message);
+    let _ = writer.flush();
+    let line = {
+        let _echo = EchoOff::new();
+        read_line()
+    };
+    /* The enter key was not echoed either. */
+    let _ = writeln!(writer);
+    line
+}
+
+/* Turns terminal echo off for its lifetime, restored on drop so a panic or error cannot leave it off. */
+struct EchoOff {
+    #[cfg(windows)]
+    mode: Option<(console::Handle, u32)>,
+}
+
+#[cfg(unix)]
+impl EchoOff {
+    fn new() -> Self {
+        let _ = stty(&["-echo"]);
+        Self {}
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        let _ = stty(&["echo"]);
+    }
+}
+
+#[cfg(windows)]
+impl EchoOff {
+    fn new() -> Self {
+        let mode = console::stdin_mode();
+        if let Some((handle, mode)) = mode {
+            console::set_mode(handle, mode & !console::ENABLE_ECHO_INPUT);
+        }
+        Self { mode }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        if let Some((handle, mode)) = self.mode {
+            console::set_mode(handle, mode);
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+impl EchoOff {
+    fn new() -> Self {
+        Self {}
+    }
+}
+
+impl App {
+    /* Registers -y/--yes, which answers every confirm prompt of the app with yes. */
+    pub fn add_yes_flag(&mut self) {
+        self.yes_flag = true;
+        let prefixes = &self.parser().options().prefixes;
+        let keys: Vec<String> = prefixes
+            .short_key('y')
+            .into_iter()
+            .chain(prefixes.long_key("yes"))
+            .collect();
+        for key in keys {
+            self.add_argument(
+                &key,
+                Arg::new()
+                    .help("Answer yes to every confirmation prompt")
+                    .as_flag()
+                    .optional(),
+            );
+        }
+    }
+
+    /* Yes when -y/--yes was given, asked through the injected io when App::with_io set it. */
+    pub fn confirm(&self, message: &str, default: ConfirmDefault) -> Result<bool, ParseError> {
+        let prefixes = &self.parser().options().prefixes;
+        let assumed = self.yes_flag
+            && prefixes
+                .short_key('y')
+                .into_iter()
+                .chain(prefixes.long_key("yes"))
+                .any(|key| self.args().is_given(key.as_str()));
+        match (assumed, &self.io) {
+            (true, _) => Ok(true),
+            (false, Some(io)) => {
+                let mut io = io.borrow_mut();
+                let (reader, writer) = io.split();
+                confirm_with(message, default, reader, writer)
+            }
+            (false, None) => confirm(message, default),
+        }
+    }
+}
+
+pub fn prompt_with(
+    message: impl Into<String>,
+    validator: &dyn ArgValidator,
+) -> Result<String, ParseError> {
+    Prompt::new(message).ask_validated(validator)
+}
+
+/*
+  One thread reads stdin for every timed prompt of the process. A read that timed out stays
+  pending and its line goes to the next prompt instead of being lost, though stdin read outside
+  of Prompt while it is pending still loses that line.
+*/
+struct LineReader {
+    requests: mpsc::Sender<()>,
+    lines: mpsc::Receiver<Result<String, ParseError>>,
+    pending: bool,
+}
+
+impl LineReader {
+    fn spawn() -> Self {
+        let (requests, requested) = mpsc::channel::<()>();
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            while requested.recv().is_ok() {
+                if sender
+                    .send(read_line_from(&mut io::stdin().lock()))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            lines,
+            pending: false,
+        }
+    }
+}
+
+static LINE_READER: Mutex<Option<LineReader>> = Mutex::new(None);
+
+/* None when nothing was read in time. */
+fn read_line_timeout(timeout: Duration) -> Option<Result<String, ParseError>> {
+    let mut reader = LINE_READER.lock().unwrap_or_else(|e| e.into_inner());
+    let reader = reader.get_or_insert_with(LineReader::spawn);
+    if !reader.pending {
+        reader.pending = reader.requests.send(()).is_ok();
+    }
+    let line = reader.lines.recv_timeout(timeout).ok()?;
+    reader.pending = false;
+    Some(line)
+}
+
+/* Waits for a read left pending by a timed out prompt before reading stdin itself. */
+fn read_line() -> Result<String, ParseError> {
+    let mut reader = LINE_READER.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(reader) = reader.as_mut().filter(|reader| reader.pending) {
+        reader.pending = false;
+        if let Ok(line) = reader.lines.recv() {
+            return line;
+        }
+    }
+    read_line_from(&mut io::stdin().lock())
+}
+
+fn read_line_from(reader: &mut dyn BufRead) -> Result<String, ParseError> {
+    let mut line = String::new();
+    match reader.read_line(&mut line) {
+        Ok(0) => Err(ParseError::no_value_given(format_args!(
+            "stdin closed before an answer was given"
+        ))),
+        Ok(_) => Ok(line.trim_end_matches(['\r', '\n']).to_string()),
+        Err(e) => Err(ParseError::invalid_value(format_args!(
+            "cannot read stdin: {}",
+            e
+        ))),
+    }
+}
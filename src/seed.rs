@@ -0,0 +1,132 @@
+use std::time::SystemTime;
+
+use crate::{App, Arg, ArgValidator, ParseError, paragraph, tui};
+
+/* xorshift64*, small and reproducible, not meant for cryptography. */
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    seed: u64,
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            state: match splitmix64(seed) {
+                0 => 0x9E3779B97F4A7C15,
+                v => v,
+            },
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    pub fn next_below(&mut self, bound: u64) -> u64 {
+        match bound {
+            0 => 0,
+            v => self.next_u64() % v,
+        }
+    }
+
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn splitmix64(v: u64) -> u64 {
+    let mut z = v.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+pub fn generate_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+        ^ std::process::id() as u64
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SeedValidator;
+
+impl ArgValidator for SeedValidator {
+    fn id(&self) -> Option<String> {
+        Some(String::from("SeedValidator"))
+    }
+    fn help(&self) -> Option<tui::DomNode> {
+        Some(paragraph!("Unsigned 64 bit integer"))
+    }
+    fn validate(&self, v: Option<&str>) -> Result<(), ParseError> {
+        match v.map(str::parse::<u64>) {
+            Some(Err(_)) => Err(ParseError::invalid_value(format_args!(
+                "{} is not a valid seed",
+                v.unwrap_or_default()
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl App {
+    /* Not registered when the key prefixes have no long form. */
+    pub fn add_seed_flag(&mut self) {
+        let Some(key) = self.seed_key() else {
+            return;
+        };
+        self.add_argument(
+            &key,
+            Arg::new()
+                .help("Seed for randomized behavior, generated and logged when omitted")
+                .require_value()
+                .validate(SeedValidator)
+                .optional(),
+        );
+    }
+
+    fn seed_key(&self) -> Option<String> {
+        self.parser().options().prefixes.long_key("seed")
+    }
+
+    /* The seed is fixed on first use so every rng() handle of a run agrees. */
+    pub fn seed(&mut self) -> u64 {
+        if let Some(seed) = self.seed {
+            return seed;
+        }
+        let key = self.seed_key().unwrap_or_default();
+        let seed = match self
+            .args()
+            .all_param_iter()
+            .find(|(k, _)| key.as_str() == k)
+            .and_then(|(_, v)| v.parse::<u64>().ok())
+        {
+            Some(seed) => seed,
+            None => {
+                let seed = generate_seed();
+                #[cfg(feature = "log")]
+                crate::log::info(format_args!(
+                    "generated seed {}, pass {} {} to reproduce",
+                    seed, key, seed
+                ));
+                seed
+            }
+        };
+        self.seed = Some(seed);
+        seed
+    }
+
+    pub fn rng(&mut self) -> SeededRng {
+        SeededRng::new(self.seed())
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::{Path, PathBuf};
+
+use crate::{App, ParseError};
+
+/*
+  Key value state shared between actions. With a backing file it is loaded on first use and
+  saved when the App is dropped, one `key=value` per line with `\n` and `\\` escaped.
+*/
+#[derive(Debug, Default, Clone)]
+pub struct Session {
+    values: Vec<(String, String)>,
+    path: Option<PathBuf>,
+    dirty: bool,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /* A missing file is an empty session that will be created on save. */
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, ParseError> {
+        let path = path.into();
+        let mut session = Self {
+            path: Some(path.clone()),
+            ..Self::default()
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(session),
+            Err(e) => {
+                return Err(ParseError::invalid_value(format_args!(
+                    "cannot read {}: {}",
+                    path.display(),
+                    e
+                )));
+            }
+        };
+        for line in content.lines().filter(|line| !line.is_empty()) {
+            if let Some((k, v)) = line.split_once('=') {
+                session.values.push((unescape(k), unescape(v)));
+            }
+        }
+        Ok(session)
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        let (key, value) = (key.into(), value.into());
+        match self.values.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => self.values.push((key, value)),
+        }
+        self.dirty = true;
+        self
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let id = self.values.iter().position(|(k, _)| k == key)?;
+        self.dirty = true;
+        Some(self.values.remove(id).1)
+    }
+
+    pub fn clear(&mut self) {
+        self.dirty |= !self.values.is_empty();
+        self.values.clear();
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /* Writes the backing file if anything changed, a no-op for in-memory sessions. */
+    pub fn save(&mut self) -> Result<(), ParseError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty {
+            return Ok(());
+        }
+        let content: String = self
+            .values
+            .iter()
+            .map(|(k, v)| format!("{}={}\n", escape(k).replace('=', "\\e"), escape(v)))
+            .collect();
+        std::fs::write(path, content).map_err(|e| {
+            ParseError::invalid_value(format_args!("cannot write {}: {}", path.display(), e))
+        })?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+fn escape(v: &str) -> String {
+    v.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(v: &str) -> String {
+    let mut out = String::with_capacity(v.len());
+    let mut chars = v.chars();
+    while let Some(c) = chars.next() {
+        match (c, c == '\\') {
+            (_, true) => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('e') => out.push('='),
+                Some(c) => out.push(c),
+                None => out.push('\\'),
+            },
+            (c, false) => out.push(c),
+        }
+    }
+    out
+}
+
+impl App {
+    pub fn session_file(&mut self, path: impl Into<PathBuf>) {
+        self.session = None;
+        self.session_path = Some(path.into());
+    }
+
+    /* Loads the session file on first use, a file that cannot be read starts an empty session. */
+    pub fn session(&mut self) -> &mut Session {
+        if self.session.is_none() {
+            let session = match self.session_path.clone().map(Session::load) {
+                None => Session::new(),
+                Some(Ok(session)) => session,
+                Some(Err(e)) => {
+                    self.add_warning(format!("session is not restored: {}", e.msg));
+                    Session::new()
+                }
+            };
+            self.session = Some(session);
+        }
+        self.session.as_mut().unwrap()
+    }
+
+    pub(crate) fn save_session(&mut self) {
+        if let Some(session) = &mut self.session
+            && let Err(e) = session.save()
+        {
+            self.write_err(&format!("{}\n", e));
+        }
+    }
+}
@@ -1,5 +1,21 @@
 use std::collections::HashSet;
 use std::fmt::{self, Display};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+pub mod input;
+mod panel;
+mod size;
+mod spinner;
+mod table;
+mod theme;
+
+pub use panel::*;
+pub use size::*;
+pub use spinner::*;
+pub use table::*;
+pub use theme::*;
+
+pub use crate::prompt;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RgbColor {
@@ -94,6 +110,46 @@ impl RgbColor {
     }
 }
 
+/* How many colors the terminal can show, other colors are drawn as the nearest one it has. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /* From COLORTERM, which truecolor terminals set to truecolor or 24bit, then a 256color TERM. */
+    pub fn detect() -> Self {
+        match std::env::var("COLORTERM").as_deref() {
+            Ok("truecolor" | "24bit") => Self::TrueColor,
+            _ if std::env::var("TERM").is_ok_and(|term| term.contains("256color")) => Self::Ansi256,
+            _ => Self::Basic,
+        }
+    }
+}
+
+/* 0 until set or detected, then ColorDepth as 1 + its index. */
+static COLOR_DEPTH: AtomicU8 = AtomicU8::new(0);
+
+/* Overrides the detected depth for everything rendered afterwards. */
+pub fn set_color_depth(depth: ColorDepth) {
+    COLOR_DEPTH.store(depth as u8 + 1, Ordering::Relaxed);
+}
+
+pub fn color_depth() -> ColorDepth {
+    match COLOR_DEPTH.load(Ordering::Relaxed) {
+        0 => {
+            let depth = ColorDepth::detect();
+            set_color_depth(depth);
+            depth
+        }
+        1 => ColorDepth::Basic,
+        2 => ColorDepth::Ansi256,
+        _ => ColorDepth::TrueColor,
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TextEffect {
     Bold,
@@ -110,6 +166,9 @@ pub enum TextEffect {
 #[derive(Debug, Default, Clone)]
 pub struct DomStyle {
     indentation: u32,
+    padding: u32,
+    margin: u32,
+    align: Option<Align>,
     effects: Option<HashSet<TextEffect>>,
     bg: Option<RgbColor>,
     fg: Option<RgbColor>,
@@ -125,6 +184,24 @@ impl DomStyle {
         self
     }
 
+    /* Blank lines above and below and spaces left and right, inside the colors. */
+    pub fn padding(mut self, v: u32) -> Self {
+        self.padding = v;
+        self
+    }
+
+    /* Like padding but outside the colors, spacing the layout from its neighbours. */
+    pub fn margin(mut self, v: u32) -> Self {
+        self.margin = v;
+        self
+    }
+
+    /* Where paragraphs sit in the layout's width, without a width they stay left. */
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = Some(align);
+        self
+    }
+
     pub fn effects<I: IntoIterator<Item = TextEffect>>(mut self, effects: I) -> Self {
         for effect in effects {
             self.effects.get_or_insert_with(HashSet::new).insert(effect);
@@ -146,12 +223,36 @@ impl DomStyle {
         self.fg = Some(color);
         self
     }
+
+    /* Whether the style changes how text looks, spacing aside. */
+    fn is_plain(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none() && self.effects.is_none() && self.align.is_none()
+    }
+
+    /*
+      The style text inside a layout gets: colors and alignment set here win over the parent's,
+      effects add up. Spacing belongs to the layout and is not passed on.
+    */
+    fn cascade(&self, parent: &DomStyle) -> DomStyle {
+        let effects = match (&parent.effects, &self.effects) {
+            (Some(parent), Some(own)) => Some(parent.union(own).copied().collect()),
+            (parent, own) => own.clone().or_else(|| parent.clone()),
+        };
+        DomStyle {
+            effects,
+            bg: self.bg.or(parent.bg),
+            fg: self.fg.or(parent.fg),
+            align: self.align.or(parent.align),
+            ..DomStyle::default()
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct Layout {
     children: Vec<DomNode>,
     style: DomStyle,
+    width: Option<usize>,
 }
 
 impl Layout {
@@ -164,6 +265,21 @@ impl Layout {
         self
     }
 
+    /*
+      Columns available to everything inside, paragraphs wrap and tables shrink to fit. Nested
+      layouts inherit it, a paragraph's own wrap width wins.
+    */
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /* Sets the width to the terminal's, left unset when there is no terminal to measure. */
+    pub fn fit_terminal(mut self) -> Self {
+        self.width = terminal_width().or(self.width);
+        self
+    }
+
     pub fn append_child<N: Into<DomNode>>(mut self, child: N) -> Self {
         self.children.push(child.into());
         self
@@ -196,6 +312,8 @@ impl Layout {
 pub struct Paragraph {
     text: String,
     newline: bool,
+    wrap: Option<usize>,
+    hanging: usize,
 }
 
 impl Paragraph {
@@ -203,18 +321,105 @@ impl Paragraph {
         Self {
             text: fmt::format(args),
             newline: true,
+            wrap: None,
+            hanging: 0,
         }
     }
     pub fn no_newline(mut self) -> Self {
         self.newline = false;
         self
     }
+    /* Breaks at spaces so no line is wider than width columns, counting the layout indent. */
+    pub fn wrap(mut self, width: usize) -> Self {
+        self.wrap = Some(width);
+        self
+    }
+    /* Extra indent for the continuation lines of a wrapped paragraph, e.g. under a list marker. */
+    pub fn hanging_indent(mut self, indent: usize) -> Self {
+        self.hanging = indent;
+        self
+    }
+
+    /* The lines to print once wrapped, words longer than a whole line are split. */
+    fn lines(&self, indent: usize, width: Option<usize>) -> Vec<String> {
+        let Some(width) = self.wrap.or(width) else {
+            return vec![self.text.clone()];
+        };
+        /* Every line after the first one gets the hanging indent. */
+        let avail = |lines: &[String]| {
+            let hanging = if lines.is_empty() { 0 } else { self.hanging };
+            width.saturating_sub(indent + hanging).max(1)
+        };
+        let mut lines = Vec::new();
+        for text in self.text.split('\n') {
+            let mut line = String::new();
+            let mut line_width = 0;
+            for word in text.split(' ').filter(|word| !word.is_empty()) {
+                let word_width = display_width(word);
+                if !line.is_empty() && line_width + 1 + word_width > avail(&lines) {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                let mut word = word;
+                while line.is_empty() && display_width(word) > avail(&lines) {
+                    let cut = split_at_width(word, avail(&lines));
+                    /* A single character wider than the line stays whole. */
+                    if cut == word.len() {
+                        break;
+                    }
+                    lines.push(word[..cut].to_string());
+                    word = &word[cut..];
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                    line_width += 1;
+                }
+                line.push_str(word);
+                line_width += display_width(word);
+            }
+            lines.push(line);
+        }
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(id, line)| match id {
+                0 => line,
+                _ => format!("{:hanging$}{}", "", line, hanging = self.hanging),
+            })
+            .collect()
+    }
+}
+
+/* Cuts text to width columns, ending in an ellipsis when anything was cut. */
+pub fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    match width {
+        0 => String::new(),
+        1 => String::from("…"),
+        _ => format!("{}…", &text[..split_at_width(text, width - 1)]),
+    }
+}
+
+/* Byte offset where the first width columns of text end, at least one character. */
+fn split_at_width(text: &str, width: usize) -> usize {
+    let mut used = 0;
+    for (at, c) in text.char_indices() {
+        used += char_width(c);
+        if used > width && at > 0 {
+            return at;
+        }
+    }
+    text.len()
 }
 
 #[derive(Debug, Clone)]
 pub enum DomNode {
     VStack(Layout),
     Text(Paragraph),
+    Table(Table),
+    Panel(Panel),
 }
 
 pub use DomNode::VStack;
@@ -225,12 +430,141 @@ impl From<Paragraph> for DomNode {
     }
 }
 
+impl From<Panel> for DomNode {
+    fn from(value: Panel) -> Self {
+        Self::Panel(value)
+    }
+}
+
+impl From<Table> for DomNode {
+    fn from(value: Table) -> Self {
+        Self::Table(value)
+    }
+}
+
 impl From<Layout> for DomNode {
     fn from(value: Layout) -> Self {
         Self::VStack(value)
     }
 }
 
+/* Auto follows NO_COLOR, then CLICOLOR_FORCE, then whether the stream is a terminal. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(self, is_terminal: bool) -> bool {
+        let set = |var: &str| std::env::var(var).is_ok_and(|v| !v.is_empty());
+        /* CLICOLOR_FORCE=0 forces nothing, the terminal check still applies. */
+        let forced =
+            set("CLICOLOR_FORCE") && std::env::var("CLICOLOR_FORCE").is_ok_and(|v| v != "0");
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto if set("NO_COLOR") => false,
+            Self::Auto if forced => true,
+            Self::Auto => is_terminal,
+        }
+    }
+}
+
+impl DomNode {
+    pub fn render(&self, color: bool) -> String {
+        match color {
+            true => self.to_string(),
+            false => strip_ansi(&self.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    /* Pads text with spaces to width terminal columns, text already as wide is returned as is. */
+    pub fn pad(&self, text: &str, width: usize) -> String {
+        let fill = width.saturating_sub(display_width(text));
+        let (left, right) = match self {
+            Self::Left => (0, fill),
+            Self::Center => (fill / 2, fill - fill / 2),
+            Self::Right => (fill, 0),
+        };
+        format!("{:left$}{}{:right$}", "", text, "")
+    }
+}
+
+/* Terminal columns taken by text: East Asian wide characters and emoji count 2, combining marks 0. */
+pub fn display_width(text: &str) -> usize {
+    strip_ansi(text).chars().map(char_width).sum()
+}
+
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x00..=0x1f | 0x7f..=0x9f => 0,
+        0x0300..=0x036f
+        | 0x1ab0..=0x1aff
+        | 0x1dc0..=0x1dff
+        | 0x200b..=0x200f
+        | 0x20d0..=0x20ff
+        | 0xfe00..=0xfe0f
+        | 0xfe20..=0xfe2f => 0,
+        0x1100..=0x115f
+        | 0x2e80..=0x303e
+        | 0x3041..=0x33ff
+        | 0x3400..=0x4dbf
+        | 0x4e00..=0x9fff
+        | 0xa000..=0xa4cf
+        | 0xac00..=0xd7a3
+        | 0xf900..=0xfaff
+        | 0xfe30..=0xfe4f
+        | 0xff00..=0xff60
+        | 0xffe0..=0xffe6
+        | 0x1f300..=0x1f64f
+        | 0x1f900..=0x1f9ff
+        | 0x20000..=0x3fffd => 2,
+        _ => 1,
+    }
+}
+
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
 #[macro_export]
 macro_rules! paragraph {
     ($($args: expr), *) => {
@@ -240,7 +574,10 @@ macro_rules! paragraph {
 mod ansi {
     use std::fmt;
 
-    use crate::tui::{DomNode, DomStyle, Layout, Paragraph, RgbColor, TextEffect};
+    use crate::tui::{
+        Align, ColorDepth, DomNode, DomStyle, Layout, Paragraph, RgbColor, TextEffect, color_depth,
+        display_width,
+    };
 
     static ANSI_BG_MAP: [(RgbColor, u32); 16] = [
         (RgbColor::black(), 40),
@@ -292,7 +629,50 @@ mod ansi {
         (TextEffect::DoubleUnderline, 9),
     ];
 
-    fn render_style(style: &DomStyle) -> Option<String> {
+    /* Levels of each channel in the 6x6x6 cube of the 256 color palette. */
+    static CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    fn distance(a: RgbColor, b: RgbColor) -> u32 {
+        let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+        d(a.r, b.r) + d(a.g, b.g) + d(a.b, b.b)
+    }
+
+    /* Index of the closest cube or grey ramp entry, 16 to 255. */
+    fn nearest_256(color: RgbColor) -> u32 {
+        let level = |v: u8| {
+            (0..CUBE_LEVELS.len())
+                .min_by_key(|id| (CUBE_LEVELS[*id] as i32 - v as i32).abs())
+                .unwrap_or(0)
+        };
+        let (r, g, b) = (level(color.r), level(color.g), level(color.b));
+        let cube = RgbColor::new(CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+        let avg = (color.r as u32 + color.g as u32 + color.b as u32) / 3;
+        let grey_id = (avg.saturating_sub(3) / 10).min(23);
+        let grey_level = (8 + grey_id * 10) as u8;
+        let grey = RgbColor::new(grey_level, grey_level, grey_level);
+        match distance(color, grey) < distance(color, cube) {
+            true => 232 + grey_id,
+            false => 16 + (36 * r + 6 * g + b) as u32,
+        }
+    }
+
+    /* A named color keeps its 16 color code, others are sent as is or as the nearest supported. */
+    fn color_code(color: RgbColor, map: &[(RgbColor, u32); 16], extended: u32) -> String {
+        if let Some((_, code)) = map.iter().find(|(key, _)| *key == color) {
+            return code.to_string();
+        }
+        match color_depth() {
+            ColorDepth::TrueColor => format!("{};2;{};{};{}", extended, color.r, color.g, color.b),
+            ColorDepth::Ansi256 => format!("{};5;{}", extended, nearest_256(color)),
+            ColorDepth::Basic => map
+                .iter()
+                .min_by_key(|(key, _)| distance(*key, color))
+                .map(|(_, code)| code.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub(super) fn render_style(style: &DomStyle) -> Option<String> {
         let mut codes: Vec<String> = Vec::new();
         if let Some(effects) = &style.effects {
             for effect in effects.iter() {
@@ -306,25 +686,11 @@ mod ansi {
                 }
             }
         }
-        if let Some(bg) = style.bg
-            && let Some(code) = ANSI_BG_MAP.iter().find_map(|(key, code)| {
-                if key == &bg {
-                    return Some(code.to_string());
-                }
-                None
-            })
-        {
-            codes.push(code);
+        if let Some(bg) = style.bg {
+            codes.push(color_code(bg, &ANSI_BG_MAP, 48));
         }
-        if let Some(fg) = style.fg
-            && let Some(code) = ANSI_FG_MAP.iter().find_map(|(key, code)| {
-                if key == &fg {
-                    return Some(code.to_string());
-                }
-                None
-            })
-        {
-            codes.push(code);
+        if let Some(fg) = style.fg {
+            codes.push(color_code(fg, &ANSI_FG_MAP, 38));
         }
         match codes.len() {
             0 => None,
@@ -333,18 +699,30 @@ mod ansi {
     }
 
     pub fn render_dom(dom: &DomNode, buf: &mut impl fmt::Write) -> Result<(), fmt::Error> {
-        recursive_render_dom(dom, buf, 0, None)
+        recursive_render_dom(dom, buf, 0, &DomStyle::default(), None)
     }
 
-    fn recursive_render_dom(
+    pub(super) fn recursive_render_dom(
         dom: &DomNode,
         buf: &mut impl fmt::Write,
         indent: usize,
-        prev_style: Option<&String>,
+        inherited: &DomStyle,
+        width: Option<usize>,
     ) -> Result<(), fmt::Error> {
+        let prev_style = render_style(inherited);
         match dom {
-            DomNode::VStack(layout) => recursive_render_vstack(layout, buf, indent, prev_style),
-            DomNode::Text(paragraph) => recursive_render_text(paragraph, buf, indent),
+            DomNode::VStack(layout) => {
+                recursive_render_vstack(layout, buf, indent, inherited, width)
+            }
+            DomNode::Text(paragraph) => recursive_render_text(
+                paragraph,
+                buf,
+                indent,
+                width,
+                inherited.align.unwrap_or_default(),
+            ),
+            DomNode::Table(table) => table.render(buf, indent, prev_style.as_ref(), width),
+            DomNode::Panel(panel) => panel.render(buf, indent, prev_style.as_ref(), width),
         }
     }
 
@@ -356,26 +734,94 @@ mod ansi {
         dom: &Layout,
         buf: &mut impl fmt::Write,
         indent: usize,
-        prev_style: Option<&String>,
+        inherited: &DomStyle,
+        width: Option<usize>,
     ) -> Result<(), fmt::Error> {
-        let cur_codes = render_style(&dom.style);
-        if let Some(code_str) = &cur_codes {
+        let (margin, padding) = (dom.style.margin as usize, dom.style.padding as usize);
+        let spacing = margin + padding;
+        let restyled = !dom.style.is_plain();
+        let style = dom.style.cascade(inherited);
+        write!(buf, "{}", "\n".repeat(margin))?;
+        if restyled && margin > 0 {
+            render_margined(dom, buf, indent, inherited, &style, width)?;
+            write!(buf, "{}", "\n".repeat(margin))?;
+            return Ok(());
+        }
+        if restyled {
             reset_format(buf)?;
-            write!(buf, "{}", code_str)?;
+            if let Some(code_str) = render_style(&style) {
+                write!(buf, "{}", code_str)?;
+            }
         }
+        write!(buf, "{}", "\n".repeat(padding))?;
+        let indent = indent + dom.style.indentation as usize + spacing;
+        let width = dom
+            .width
+            .or(width)
+            .map(|width| width.saturating_sub(spacing));
+        for child in dom.iter() {
+            recursive_render_dom(child, buf, indent, &style, width)?;
+        }
+        write!(buf, "{}", "\n".repeat(padding))?;
+        if restyled {
+            reset_format(buf)?;
+            if let Some(s) = render_style(inherited) {
+                write!(buf, "{}", s)?;
+            }
+        }
+        write!(buf, "{}", "\n".repeat(margin))?;
+        Ok(())
+    }
+
+    /*
+      Renders the body on its own so each line can start with the margin in the parent's style,
+      then the layout's style, or whichever a nested layout left open on the line before.
+    */
+    fn render_margined(
+        dom: &Layout,
+        buf: &mut impl fmt::Write,
+        indent: usize,
+        inherited: &DomStyle,
+        style: &DomStyle,
+        width: Option<usize>,
+    ) -> Result<(), fmt::Error> {
+        let (margin, padding) = (dom.style.margin as usize, dom.style.padding as usize);
+        let outer = indent + margin;
+        let width = dom
+            .width
+            .or(width)
+            .map(|width| width.saturating_sub(outer + margin + padding));
+        let mut body = "\n".repeat(padding);
         for child in dom.iter() {
             recursive_render_dom(
                 child,
-                buf,
-                indent + dom.style.indentation as usize,
-                cur_codes.as_ref(),
+                &mut body,
+                dom.style.indentation as usize + padding,
+                style,
+                width,
             )?;
         }
-        if cur_codes.is_some() {
-            reset_format(buf)?;
-        }
-        if let Some(s) = prev_style {
-            write!(buf, "{}", s)?;
+        body.push_str(&"\n".repeat(padding));
+        let restore = render_style(inherited).unwrap_or_default();
+        let mut active = render_style(style).unwrap_or_default();
+        for line in body.split_inclusive('\n') {
+            let text = line.strip_suffix('\n').unwrap_or(line);
+            if !text.is_empty() {
+                let outer = if display_width(text) > 0 { outer } else { 0 };
+                write!(buf, "{:outer$}{}{}", "", active, text)?;
+                reset_format(buf)?;
+                write!(buf, "{}", restore)?;
+            }
+            if let Some(at) = text.rfind("\x1b[") {
+                let seq = &text[at..];
+                active = match &seq[..seq.find('m').map_or(0, |end| end + 1)] {
+                    "\x1b[0m" => String::new(),
+                    seq => seq.to_string(),
+                };
+            }
+            if text.len() < line.len() {
+                writeln!(buf)?;
+            }
         }
         Ok(())
     }
@@ -384,13 +830,24 @@ mod ansi {
         dom: &Paragraph,
         buf: &mut impl fmt::Write,
         indent: usize,
+        width: Option<usize>,
+        align: Align,
     ) -> Result<(), fmt::Error> {
-        write!(buf, "{:indent$}", "")?;
-        if dom.newline {
-            writeln!(buf, "{}", dom.text)
-        } else {
-            write!(buf, "{}", dom.text)
+        let lines = dom.lines(indent, width);
+        let span = dom.wrap.or(width).map(|width| width.saturating_sub(indent));
+        for (id, line) in lines.iter().enumerate() {
+            match span {
+                Some(span) if align != Align::Left => {
+                    let line = align.pad(line, span);
+                    write!(buf, "{:indent$}{}", "", line.trim_end())?
+                }
+                _ => write!(buf, "{:indent$}{}", "", line)?,
+            }
+            if dom.newline || id + 1 < lines.len() {
+                writeln!(buf)?;
+            }
         }
+        Ok(())
     }
 }
 
@@ -402,12 +859,75 @@ impl Display for DomNode {
 
 impl Display for Paragraph {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        ansi::recursive_render_text(self, f, 0)
+        ansi::recursive_render_text(self, f, 0, None, Align::Left)
     }
 }
 
 impl Display for Layout {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        ansi::recursive_render_vstack(self, f, 0, None)
+        ansi::recursive_render_vstack(self, f, 0, &DomStyle::default(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Paragraph;
+
+    fn wrap(text: &str, width: usize, hanging: usize) -> Vec<String> {
+        Paragraph::new(format_args!("{}", text))
+            .wrap(width)
+            .hanging_indent(hanging)
+            .lines(0, None)
+    }
+
+    #[test]
+    fn keeps_text_without_a_width() {
+        let paragraph = Paragraph::new(format_args!("a b  c"));
+        assert_eq!(paragraph.lines(2, None), ["a b  c"]);
+    }
+
+    #[test]
+    fn breaks_at_spaces() {
+        assert_eq!(wrap("aaa bbb ccc ddd", 10, 0), ["aaa bbb", "ccc ddd"]);
+        assert_eq!(wrap("aaa bbb", 7, 0), ["aaa bbb"]);
+        assert_eq!(wrap("a\nb c", 3, 0), ["a", "b c"]);
+        assert_eq!(
+            Paragraph::new(format_args!("aaa bbb"))
+                .wrap(9)
+                .lines(4, None),
+            ["aaa", "bbb"]
+        );
+    }
+
+    #[test]
+    fn first_line_has_no_hanging_indent() {
+        assert_eq!(wrap("ab cd efgh ij", 10, 2), ["ab cd efgh", "  ij"]);
+        assert_eq!(
+            wrap("- alpha beta gamma", 10, 2),
+            ["- alpha", "  beta", "  gamma"]
+        );
+        assert_eq!(wrap("a\nb", 10, 2), ["a", "  b"]);
+    }
+
+    #[test]
+    fn splits_long_words_with_hanging_indent() {
+        assert_eq!(wrap("abcdefghij", 6, 2), ["abcdef", "  ghij"]);
+        assert_eq!(
+            wrap("ab abcdefghij", 6, 2),
+            ["ab", "  abcd", "  efgh", "  ij"]
+        );
+        assert_eq!(wrap("abc", 1, 3), ["a", "   b", "   c"]);
+    }
+
+    #[test]
+    fn counts_wide_characters() {
+        assert_eq!(wrap("日本語テキスト", 5, 0), ["日本", "語テ", "キス", "ト"]);
+        assert_eq!(
+            wrap("日本語テキスト", 5, 1),
+            ["日本", " 語テ", " キス", " ト"]
+        );
+        assert_eq!(wrap("日本 語テ キス", 7, 0), ["日本", "語テ", "キス"]);
+        assert_eq!(wrap("日本 語", 7, 0), ["日本 語"]);
+        assert_eq!(wrap("日本", 1, 0), ["日", "本"]);
     }
 }
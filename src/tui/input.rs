@@ -0,0 +1,333 @@
+use std::io::{self, Read};
+
+/* One key press decoded from raw terminal input. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEvent {
+    Char(char),
+    Ctrl(char),
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Delete,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Unknown,
+}
+
+impl KeyEvent {
+    /*
+      Decodes the key at the start of bytes and how many bytes it used, None when bytes is empty
+      or ends inside a UTF-8 character. A lone ESC is the escape key.
+    */
+    pub fn parse(bytes: &[u8]) -> Option<(Self, usize)> {
+        let (&first, rest) = bytes.split_first()?;
+        let key = match first {
+            b'\r' | b'\n' => Self::Enter,
+            b'\t' => Self::Tab,
+            0x7f | 0x08 => Self::Backspace,
+            0x1b => return Some(Self::parse_escape(rest)),
+            0x01..=0x1a => Self::Ctrl((b'a' + first - 1) as char),
+            0x00..=0x1f => Self::Unknown,
+            _ => {
+                let len = match first {
+                    0xc0..=0xdf => 2,
+                    0xe0..=0xef => 3,
+                    0xf0..=0xf7 => 4,
+                    _ => 1,
+                };
+                let text = bytes.get(..len)?;
+                /* A broken sequence only drops its first byte, the next one may start a key. */
+                return Some(
+                    match std::str::from_utf8(text)
+                        .ok()
+                        .and_then(|s| s.chars().next())
+                    {
+                        Some(c) => (Self::Char(c), len),
+                        None => (Self::Unknown, 1),
+                    },
+                );
+            }
+        };
+        Some((key, 1))
+    }
+
+    /* rest is what follows the ESC byte, CSI `ESC [` and SS3 `ESC O` sequences are understood. */
+    fn parse_escape(rest: &[u8]) -> (Self, usize) {
+        let Some((&intro, params)) = rest.split_first() else {
+            return (Self::Esc, 1);
+        };
+        if intro != b'[' && intro != b'O' {
+            return (Self::Esc, 1);
+        }
+        let Some(end) = params.iter().position(|b| (0x40..=0x7e).contains(b)) else {
+            return (Self::Esc, 1);
+        };
+        let key = match (params[end], &params[..end]) {
+            (b'A', _) => Self::Up,
+            (b'B', _) => Self::Down,
+            (b'C', _) => Self::Right,
+            (b'D', _) => Self::Left,
+            (b'H', _) => Self::Home,
+            (b'F', _) => Self::End,
+            (b'~', b"1" | b"7") => Self::Home,
+            (b'~', b"4" | b"8") => Self::End,
+            (b'~', b"3") => Self::Delete,
+            (b'~', b"5") => Self::PageUp,
+            (b'~', b"6") => Self::PageDown,
+            _ => Self::Unknown,
+        };
+        (key, end + 3)
+    }
+}
+
+/* Decodes key events from a byte stream, usually stdin while RawMode is active. */
+pub struct KeyReader<R: Read> {
+    input: R,
+    buf: Vec<u8>,
+}
+
+impl KeyReader<io::Stdin> {
+    pub fn stdin() -> Self {
+        Self::new(io::stdin())
+    }
+}
+
+impl<R: Read> KeyReader<R> {
+    pub fn new(input: R) -> Self {
+        Self {
+            input,
+            buf: Vec::new(),
+        }
+    }
+
+    /* Blocks until a whole key is available, end of input is an UnexpectedEof error. */
+    pub fn read_key(&mut self) -> io::Result<KeyEvent> {
+        loop {
+            if let Some((key, len)) = KeyEvent::parse(&self.buf) {
+                self.buf.drain(..len);
+                return Ok(key);
+            }
+            let mut chunk = [0; 32];
+            match self.input.read(&mut chunk)? {
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                n => self.buf.extend_from_slice(&chunk[..n]),
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for KeyReader<R> {
+    type Item = KeyEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_key().ok()
+    }
+}
+
+/* Puts the terminal into raw mode: no echo, no line buffering and no signal keys. Restored on drop. */
+pub struct RawMode {
+    #[cfg(unix)]
+    saved: String,
+    #[cfg(windows)]
+    saved: (console::Handle, u32),
+}
+
+#[cfg(unix)]
+impl RawMode {
+    pub fn enable() -> io::Result<Self> {
+        let output = std::process::Command::new("stty")
+            .arg("-g")
+            .stdin(std::process::Stdio::inherit())
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::other("stdin is not a terminal"));
+        }
+        let saved = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        stty(&["raw", "-echo"])?;
+        Ok(Self { saved })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[self.saved.as_str()]);
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn stty(settings: &[&str]) -> io::Result<()> {
+    std::process::Command::new("stty")
+        .args(settings)
+        .stdin(std::process::Stdio::inherit())
+        .status()
+        .map(|_| ())
+}
+
+#[cfg(windows)]
+impl RawMode {
+    pub fn enable() -> io::Result<Self> {
+        use console::{ENABLE_ECHO_INPUT, ENABLE_LINE_INPUT, ENABLE_PROCESSED_INPUT};
+        let (handle, mode) =
+            console::stdin_mode().ok_or_else(|| io::Error::other("stdin is not a console"))?;
+        console::set_mode(
+            handle,
+            mode & !(ENABLE_ECHO_INPUT | ENABLE_LINE_INPUT | ENABLE_PROCESSED_INPUT),
+        );
+        Ok(Self {
+            saved: (handle, mode),
+        })
+    }
+}
+
+#[cfg(windows)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        console::set_mode(self.saved.0, self.saved.1);
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+impl RawMode {
+    pub fn enable() -> io::Result<Self> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(windows)]
+pub(crate) mod console {
+    pub type Handle = *mut std::ffi::c_void;
+
+    pub const ENABLE_PROCESSED_INPUT: u32 = 0x0001;
+    pub const ENABLE_LINE_INPUT: u32 = 0x0002;
+    pub const ENABLE_ECHO_INPUT: u32 = 0x0004;
+    const STD_INPUT_HANDLE: u32 = -10i32 as u32;
+
+    unsafe extern "system" {
+        fn GetStdHandle(std_handle: u32) -> Handle;
+        fn GetConsoleMode(handle: Handle, mode: *mut u32) -> i32;
+        fn SetConsoleMode(handle: Handle, mode: u32) -> i32;
+    }
+
+    /* None when stdin is not a console. */
+    pub fn stdin_mode() -> Option<(Handle, u32)> {
+        let mut mode = 0;
+        let handle = unsafe { GetStdHandle(STD_INPUT_HANDLE) };
+        match unsafe { GetConsoleMode(handle, &mut mode) } {
+            0 => None,
+            _ => Some((handle, mode)),
+        }
+    }
+
+    pub fn set_mode(handle: Handle, mode: u32) {
+        unsafe { SetConsoleMode(handle, mode) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyEvent, KeyReader};
+
+    fn keys(bytes: &[u8]) -> Vec<KeyEvent> {
+        KeyReader::new(bytes).collect()
+    }
+
+    #[test]
+    fn decodes_single_bytes() {
+        assert_eq!(KeyEvent::parse(b"a"), Some((KeyEvent::Char('a'), 1)));
+        assert_eq!(KeyEvent::parse(b"\r"), Some((KeyEvent::Enter, 1)));
+        assert_eq!(KeyEvent::parse(b"\n"), Some((KeyEvent::Enter, 1)));
+        assert_eq!(KeyEvent::parse(b"\t"), Some((KeyEvent::Tab, 1)));
+        assert_eq!(KeyEvent::parse(b"\x7f"), Some((KeyEvent::Backspace, 1)));
+        assert_eq!(KeyEvent::parse(b"\x08"), Some((KeyEvent::Backspace, 1)));
+        assert_eq!(KeyEvent::parse(b"\x01"), Some((KeyEvent::Ctrl('a'), 1)));
+        assert_eq!(KeyEvent::parse(b"\x1a"), Some((KeyEvent::Ctrl('z'), 1)));
+        assert_eq!(KeyEvent::parse(b"\x00"), Some((KeyEvent::Unknown, 1)));
+        assert_eq!(KeyEvent::parse(b"\x1f"), Some((KeyEvent::Unknown, 1)));
+        assert_eq!(KeyEvent::parse(b""), None);
+    }
+
+    #[test]
+    fn decodes_utf8() {
+        assert_eq!(
+            KeyEvent::parse("é".as_bytes()),
+            Some((KeyEvent::Char('é'), 2))
+        );
+        assert_eq!(
+            KeyEvent::parse("€".as_bytes()),
+            Some((KeyEvent::Char('€'), 3))
+        );
+        assert_eq!(
+            KeyEvent::parse("🦀x".as_bytes()),
+            Some((KeyEvent::Char('🦀'), 4))
+        );
+        /* Incomplete, more bytes are needed. */
+        assert_eq!(KeyEvent::parse(&"€".as_bytes()[..2]), None);
+    }
+
+    #[test]
+    fn decodes_escape_sequences() {
+        let cases: [(&[u8], KeyEvent); 13] = [
+            (b"\x1b[A", KeyEvent::Up),
+            (b"\x1b[B", KeyEvent::Down),
+            (b"\x1b[C", KeyEvent::Right),
+            (b"\x1b[D", KeyEvent::Left),
+            (b"\x1bOH", KeyEvent::Home),
+            (b"\x1bOF", KeyEvent::End),
+            (b"\x1b[1~", KeyEvent::Home),
+            (b"\x1b[8~", KeyEvent::End),
+            (b"\x1b[3~", KeyEvent::Delete),
+            (b"\x1b[5~", KeyEvent::PageUp),
+            (b"\x1b[6~", KeyEvent::PageDown),
+            (b"\x1b[1;5C", KeyEvent::Right),
+            (b"\x1b[9~", KeyEvent::Unknown),
+        ];
+        for (bytes, key) in cases {
+            assert_eq!(
+                KeyEvent::parse(bytes),
+                Some((key, bytes.len())),
+                "{bytes:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn lone_or_broken_escape_is_the_escape_key() {
+        assert_eq!(KeyEvent::parse(b"\x1b"), Some((KeyEvent::Esc, 1)));
+        assert_eq!(KeyEvent::parse(b"\x1bx"), Some((KeyEvent::Esc, 1)));
+        assert_eq!(KeyEvent::parse(b"\x1b[12"), Some((KeyEvent::Esc, 1)));
+    }
+
+    #[test]
+    fn malformed_utf8_skips_one_byte() {
+        assert_eq!(KeyEvent::parse(b"\x80"), Some((KeyEvent::Unknown, 1)));
+        assert_eq!(KeyEvent::parse(b"\xff"), Some((KeyEvent::Unknown, 1)));
+        assert_eq!(keys(b"\xc3("), [KeyEvent::Unknown, KeyEvent::Char('(')]);
+    }
+
+    #[test]
+    fn reader_splits_a_stream_into_keys() {
+        assert_eq!(
+            keys("a\x1b[Aé\r\x1b".as_bytes()),
+            [
+                KeyEvent::Char('a'),
+                KeyEvent::Up,
+                KeyEvent::Char('é'),
+                KeyEvent::Enter,
+                KeyEvent::Esc,
+            ]
+        );
+        let mut reader = KeyReader::new(&[0xe2, 0x82][..]);
+        assert_eq!(
+            reader.read_key().unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+
+use crate::tui::{DomNode, DomStyle, ansi, display_width};
+
+/* Auto draws unicode box lines when the locale says UTF-8 and ASCII otherwise. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BorderChars {
+    #[default]
+    Auto,
+    Unicode,
+    Rounded,
+    Ascii,
+}
+
+impl BorderChars {
+    /* Corners top left, top right, bottom left, bottom right, then horizontal and vertical. */
+    fn chars(self) -> [char; 6] {
+        match self {
+            Self::Auto if is_utf8_locale() => Self::Unicode.chars(),
+            Self::Auto | Self::Ascii => ['+', '+', '+', '+', '-', '|'],
+            Self::Unicode => ['┌', '┐', '└', '┘', '─', '│'],
+            Self::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+        }
+    }
+}
+
+fn is_utf8_locale() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .is_some_and(|v| {
+            let v = v.to_lowercase();
+            v.contains("utf-8") || v.contains("utf8")
+        })
+        || cfg!(windows)
+}
+
+/* A box around a child node with an optional title in the top border. */
+#[derive(Debug, Clone)]
+pub struct Panel {
+    child: Box<DomNode>,
+    title: Option<String>,
+    border: BorderChars,
+    style: DomStyle,
+}
+
+impl Panel {
+    pub fn new(child: impl Into<DomNode>) -> Self {
+        Self {
+            child: Box::new(child.into()),
+            title: None,
+            border: BorderChars::default(),
+            style: DomStyle::new(),
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn border(mut self, border: BorderChars) -> Self {
+        self.border = border;
+        self
+    }
+
+    /* Style of the border and title, the child keeps its own. */
+    pub fn style(mut self, style: DomStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub(crate) fn render(
+        &self,
+        buf: &mut impl fmt::Write,
+        indent: usize,
+        prev_style: Option<&String>,
+        width: Option<usize>,
+    ) -> Result<(), fmt::Error> {
+        let [tl, tr, bl, br, h, v] = self.border.chars();
+        let mut content = String::new();
+        let inner_width = width.map(|width| width.saturating_sub(indent + 4).max(1));
+        ansi::recursive_render_dom(
+            &self.child,
+            &mut content,
+            0,
+            &DomStyle::default(),
+            inner_width,
+        )?;
+        let lines: Vec<&str> = content.lines().collect();
+        let title_width = self.title.as_deref().map_or(0, |t| display_width(t) + 1);
+        let inner = lines
+            .iter()
+            .map(|line| display_width(line))
+            .max()
+            .unwrap_or(0)
+            .max(title_width);
+        let codes = ansi::render_style(&self.style);
+        let border = |buf: &mut dyn fmt::Write, text: &str| -> fmt::Result {
+            match &codes {
+                None => write!(buf, "{}", text),
+                Some(codes) => {
+                    write!(buf, "\x1b[0m{}{}\x1b[0m", codes, text)?;
+                    if let Some(prev) = prev_style {
+                        write!(buf, "{}", prev)?;
+                    }
+                    Ok(())
+                }
+            }
+        };
+
+        let top = match &self.title {
+            None => format!("{}{}{}", tl, h.to_string().repeat(inner + 2), tr),
+            Some(title) => format!(
+                "{}{} {} {}{}",
+                tl,
+                h,
+                title,
+                h.to_string().repeat(inner - title_width),
+                tr
+            ),
+        };
+        write!(buf, "{:indent$}", "")?;
+        border(buf, &top)?;
+        writeln!(buf)?;
+        /* A style left open by the child carries over to its next line, inside the border. */
+        let mut active = String::new();
+        for line in lines {
+            write!(buf, "{:indent$}", "")?;
+            border(buf, &v.to_string())?;
+            let pad = inner - display_width(line);
+            write!(buf, " {}{}", active, line)?;
+            if !active.is_empty() || line.contains('\x1b') {
+                write!(buf, "\x1b[0m")?;
+                if let Some(prev) = prev_style {
+                    write!(buf, "{}", prev)?;
+                }
+            }
+            write!(buf, "{:pad$} ", "")?;
+            border(buf, &v.to_string())?;
+            writeln!(buf)?;
+            if let Some(at) = line.rfind("\x1b[") {
+                let seq = &line[at..];
+                let seq = &seq[..seq.find('m').map_or(0, |end| end + 1)];
+                active = match seq {
+                    "\x1b[0m" => String::new(),
+                    seq => seq.to_string(),
+                };
+            }
+        }
+        write!(buf, "{:indent$}", "")?;
+        border(
+            buf,
+            &format!("{}{}{}", bl, h.to_string().repeat(inner + 2), br),
+        )?;
+        writeln!(buf)
+    }
+}
@@ -0,0 +1,98 @@
+/* Columns and rows of the terminal on stdout, stderr or stdin, whichever is one, else COLUMNS and LINES. */
+pub fn terminal_size() -> Option<(u16, u16)> {
+    platform_size().or_else(|| Some((env_size("COLUMNS")?, env_size("LINES")?)))
+}
+
+/* Needs only COLUMNS when there is no terminal, as when piping with just COLUMNS exported. */
+pub fn terminal_width() -> Option<usize> {
+    platform_size()
+        .map(|(columns, _)| columns)
+        .or_else(|| env_size("COLUMNS"))
+        .map(|columns| columns as usize)
+}
+
+fn env_size(name: &str) -> Option<u16> {
+    std::env::var(name).ok()?.parse::<u16>().ok()
+}
+
+#[cfg(unix)]
+fn platform_size() -> Option<(u16, u16)> {
+    #[repr(C)]
+    #[derive(Default)]
+    struct WinSize {
+        rows: u16,
+        columns: u16,
+        x_pixels: u16,
+        y_pixels: u16,
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const TIOCGWINSZ: std::ffi::c_ulong = 0x5413;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    const TIOCGWINSZ: std::ffi::c_ulong = 0x40087468;
+
+    unsafe extern "C" {
+        fn ioctl(fd: std::ffi::c_int, request: std::ffi::c_ulong, ...) -> std::ffi::c_int;
+    }
+
+    [1, 2, 0].into_iter().find_map(|fd| {
+        let mut size = WinSize::default();
+        match unsafe { ioctl(fd, TIOCGWINSZ, &mut size as *mut WinSize) } {
+            0 if size.columns > 0 => Some((size.columns, size.rows)),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(windows)]
+fn platform_size() -> Option<(u16, u16)> {
+    #[repr(C)]
+    #[derive(Default)]
+    struct Coord {
+        x: i16,
+        y: i16,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct SmallRect {
+        left: i16,
+        top: i16,
+        right: i16,
+        bottom: i16,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct ScreenBufferInfo {
+        size: Coord,
+        cursor: Coord,
+        attributes: u16,
+        window: SmallRect,
+        max_window: Coord,
+    }
+
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+
+    unsafe extern "system" {
+        fn GetStdHandle(std_handle: u32) -> *mut std::ffi::c_void;
+        fn GetConsoleScreenBufferInfo(
+            handle: *mut std::ffi::c_void,
+            info: *mut ScreenBufferInfo,
+        ) -> i32;
+    }
+
+    let mut info = ScreenBufferInfo::default();
+    let handle = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) };
+    if unsafe { GetConsoleScreenBufferInfo(handle, &mut info) } == 0 {
+        return None;
+    }
+    let columns = info.window.right - info.window.left + 1;
+    let rows = info.window.bottom - info.window.top + 1;
+    Some((columns.max(0) as u16, rows.max(0) as u16))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn platform_size() -> Option<(u16, u16)> {
+    None
+}
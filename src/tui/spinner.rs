@@ -0,0 +1,180 @@
+use std::{
+    io::{self, IsTerminal},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crate::{
+    output::{self, Stream},
+    tui::{DomNode, DomStyle, Layout, Paragraph, RgbColor, strip_ansi, terminal_width, truncate},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpinnerFrames {
+    Dots,
+    Line,
+    Arc,
+    Custom(Vec<String>),
+}
+
+impl SpinnerFrames {
+    pub fn frames(&self) -> Vec<String> {
+        let frames: &[&str] = match self {
+            Self::Dots => &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+            Self::Line => &["-", "\\", "|", "/"],
+            Self::Arc => &["◜", "◠", "◝", "◞", "◡", "◟"],
+            Self::Custom(frames) => return frames.clone(),
+        };
+        frames.iter().map(|frame| frame.to_string()).collect()
+    }
+}
+
+/* Animates on stderr while work of unknown length runs, start hands back the ticking handle. */
+#[derive(Debug, Clone)]
+pub struct Spinner {
+    frames: SpinnerFrames,
+    interval: Duration,
+    style: DomStyle,
+}
+
+impl Default for Spinner {
+    fn default() -> Self {
+        Self {
+            frames: SpinnerFrames::Dots,
+            interval: Duration::from_millis(80),
+            style: DomStyle::new().fg(RgbColor::cyan()),
+        }
+    }
+}
+
+impl Spinner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn frames(mut self, frames: SpinnerFrames) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /* Style of the spinning frame, the message is printed as is. */
+    pub fn style(mut self, style: DomStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /* Without a terminal on stderr nothing animates and only the finish line is printed. */
+    pub fn start(self, message: impl Into<String>) -> SpinnerHandle {
+        let message = Arc::new(Mutex::new(message.into()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let color = io::stderr().is_terminal();
+        let thread = match color {
+            false => None,
+            true => {
+                let (message, stop) = (message.clone(), stop.clone());
+                let frames = match self.frames.frames() {
+                    frames if frames.is_empty() => vec![String::new()],
+                    frames => frames,
+                };
+                Some(std::thread::spawn(move || {
+                    for frame in frames.iter().cycle() {
+                        if stop.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let message = message.lock().map(|m| m.clone()).unwrap_or_default();
+                        draw(&styled(frame, &self.style), &message, true);
+                        std::thread::sleep(self.interval);
+                    }
+                }))
+            }
+        };
+        SpinnerHandle {
+            message,
+            stop,
+            thread,
+            color,
+        }
+    }
+}
+
+pub struct SpinnerHandle {
+    message: Arc<Mutex<String>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    color: bool,
+}
+
+impl SpinnerHandle {
+    pub fn set_message(&self, message: impl Into<String>) {
+        if let Ok(mut current) = self.message.lock() {
+            *current = message.into();
+        }
+    }
+
+    pub fn finish_ok(mut self, message: impl Into<String>) {
+        self.finish_with("✔", RgbColor::green(), &message.into());
+    }
+
+    pub fn finish_err(mut self, message: impl Into<String>) {
+        self.finish_with("✖", RgbColor::red(), &message.into());
+    }
+
+    /* Stops and clears the line without printing anything. */
+    pub fn clear(mut self) {
+        self.stop();
+    }
+
+    fn finish_with(&mut self, symbol: &str, color: RgbColor, message: &str) {
+        self.stop();
+        let symbol = styled(symbol, &DomStyle::new().fg(color));
+        draw(&symbol, message, self.color);
+        output::write(Stream::Stderr, "\n");
+        output::flush_stream(Stream::Stderr);
+    }
+
+    fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+            draw("", "", true);
+        }
+    }
+}
+
+impl Drop for SpinnerHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn styled(text: &str, style: &DomStyle) -> String {
+    DomNode::from(
+        Layout::new()
+            .style(style.clone())
+            .append_child(Paragraph::new(format_args!("{}", text)).no_newline()),
+    )
+    .to_string()
+}
+
+/* Redraws the current line in place when color is set, a terminal is assumed then. */
+fn draw(symbol: &str, message: &str, color: bool) {
+    let line = match (color, symbol.is_empty() && message.is_empty()) {
+        (true, true) => String::from("\r\x1b[2K"),
+        (true, false) => {
+            let width = terminal_width().map_or(usize::MAX, |w| w.saturating_sub(2));
+            format!("\r\x1b[2K{} {}", symbol, truncate(message, width))
+        }
+        (false, _) => format!("{} {}", strip_ansi(symbol), message),
+    };
+    output::write(Stream::Stderr, &line);
+    output::flush_stream(Stream::Stderr);
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+
+use crate::tui::{Align, DomStyle, TextEffect, ansi, display_width, truncate};
+
+#[derive(Debug, Clone, Default)]
+pub struct Cell {
+    text: String,
+    style: Option<DomStyle>,
+}
+
+impl Cell {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            style: None,
+        }
+    }
+
+    pub fn style(mut self, style: DomStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/* Columns are as wide as their widest cell, measured in terminal columns rather than bytes. */
+#[derive(Debug, Clone)]
+pub struct Table {
+    headers: Vec<Cell>,
+    rows: Vec<Vec<Cell>>,
+    align: Vec<Align>,
+    header_style: DomStyle,
+    gap: usize,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self {
+            headers: Vec::new(),
+            rows: Vec::new(),
+            align: Vec::new(),
+            header_style: DomStyle::new().effect(TextEffect::Bold),
+            gap: 2,
+        }
+    }
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn headers<C: Into<Cell>, I: IntoIterator<Item = C>>(mut self, headers: I) -> Self {
+        self.headers = headers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn row<C: Into<Cell>, I: IntoIterator<Item = C>>(mut self, row: I) -> Self {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /* Columns without an alignment are left aligned. */
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        if self.align.len() <= column {
+            self.align.resize(column + 1, Align::Left);
+        }
+        self.align[column] = align;
+        self
+    }
+
+    pub fn header_style(mut self, style: DomStyle) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /* Spaces between columns, 2 by default. */
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = Vec::new();
+        for row in std::iter::once(&self.headers).chain(self.rows.iter()) {
+            if widths.len() < row.len() {
+                widths.resize(row.len(), 0);
+            }
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(display_width(&cell.text));
+            }
+        }
+        widths
+    }
+
+    /* Narrows the widest column a column at a time until the table fits, cells get truncated. */
+    fn shrink(&self, widths: &mut [usize], width: usize) {
+        let gaps = self.gap * widths.len().saturating_sub(1);
+        while widths.iter().sum::<usize>() + gaps > width {
+            match widths.iter_mut().max() {
+                Some(widest) if *widest > 3 => *widest -= 1,
+                _ => break,
+            }
+        }
+    }
+
+    pub(crate) fn render(
+        &self,
+        buf: &mut impl fmt::Write,
+        indent: usize,
+        prev_style: Option<&String>,
+        width: Option<usize>,
+    ) -> Result<(), fmt::Error> {
+        let mut widths = self.widths();
+        if let Some(width) = width {
+            self.shrink(&mut widths, width.saturating_sub(indent));
+        }
+        if !self.headers.is_empty() {
+            let header_style = ansi::render_style(&self.header_style);
+            self.render_row(
+                &self.headers,
+                &widths,
+                header_style.as_ref(),
+                buf,
+                indent,
+                prev_style,
+            )?;
+        }
+        for row in self.rows.iter() {
+            self.render_row(row, &widths, None, buf, indent, prev_style)?;
+        }
+        Ok(())
+    }
+
+    fn render_row(
+        &self,
+        row: &[Cell],
+        widths: &[usize],
+        row_style: Option<&String>,
+        buf: &mut impl fmt::Write,
+        indent: usize,
+        prev_style: Option<&String>,
+    ) -> Result<(), fmt::Error> {
+        let empty = Cell::default();
+        write!(buf, "{:indent$}", "")?;
+        for (column, width) in widths.iter().enumerate() {
+            let cell = row.get(column).unwrap_or(&empty);
+            let align = self.align.get(column).copied().unwrap_or_default();
+            let last = column + 1 == widths.len();
+            let text = truncate(&cell.text, *width);
+            let text = match (last, align) {
+                (true, Align::Left) => text,
+                _ => align.pad(&text, *width),
+            };
+            if column > 0 {
+                write!(buf, "{:gap$}", "", gap = self.gap)?;
+            }
+            let style = cell.style.as_ref().and_then(ansi::render_style);
+            match style.as_ref().or(row_style) {
+                None => write!(buf, "{}", text)?,
+                Some(codes) => {
+                    write!(buf, "\x1b[0m{}{}\x1b[0m", codes, text)?;
+                    if let Some(prev) = prev_style {
+                        write!(buf, "{}", prev)?;
+                    }
+                }
+            }
+        }
+        writeln!(buf)
+    }
+}
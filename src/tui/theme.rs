@@ -0,0 +1,58 @@
+use crate::tui::RgbColor;
+
+/* Colors the App draws its own output in: help text, errors, warnings and the like. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub(crate) help: RgbColor,
+    pub(crate) error: RgbColor,
+    pub(crate) warning: RgbColor,
+    pub(crate) accent: RgbColor,
+    pub(crate) dimmed: RgbColor,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            help: RgbColor::bright_green(),
+            error: RgbColor::bright_yellow(),
+            warning: RgbColor::yellow(),
+            accent: RgbColor::bright_cyan(),
+            dimmed: RgbColor::bright_black(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /* The body of the help page. */
+    pub fn help(mut self, color: RgbColor) -> Self {
+        self.help = color;
+        self
+    }
+
+    /* Parse errors and errors returned by actions. */
+    pub fn error(mut self, color: RgbColor) -> Self {
+        self.error = color;
+        self
+    }
+
+    pub fn warning(mut self, color: RgbColor) -> Self {
+        self.warning = color;
+        self
+    }
+
+    /* Sections that should stand out from the help body, like the examples. */
+    pub fn accent(mut self, color: RgbColor) -> Self {
+        self.accent = color;
+        self
+    }
+
+    /* Secondary text such as the epilog. */
+    pub fn dimmed(mut self, color: RgbColor) -> Self {
+        self.dimmed = color;
+        self
+    }
+}
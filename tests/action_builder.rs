@@ -0,0 +1,115 @@
+use std::{cell::Cell, rc::Rc};
+
+use clark::{
+    ActionBuilder, App, AppIdentity, AppVersion, Arg, ArgKey, ArgValidator, OrderedArgs,
+    OutputCapture, ParseErrorKind,
+};
+
+fn app(args: &[&str]) -> App {
+    let mut app = App::new(AppIdentity::new("test", "", AppVersion::new(1, 0, 0)));
+    app.with_args(args.iter().copied());
+    app.with_io(OutputCapture::new(), std::io::empty());
+    app
+}
+
+/* Runs a single "run" action, returning whether its handler was called. */
+fn run_action(app: &mut App) -> (Result<i32, clark::ParseError>, bool) {
+    let ran = Rc::new(Cell::new(false));
+    let flag = ran.clone();
+    let res = ActionBuilder::new(app, None)
+        .add_action("run", "", move |_: &mut App| flag.set(true))
+        .run();
+    (res, ran.get())
+}
+
+#[test]
+fn requires_is_checked_before_the_handler() {
+    let mut app = app(&["--model", "m", "run"]);
+    app.add_argument("--b", Arg::new().require_value().optional());
+    app.add_argument(
+        "--model",
+        Arg::new().require_value().optional().requires("--b"),
+    );
+    let (res, ran) = run_action(&mut app);
+    assert_eq!(
+        res.unwrap_err().kind,
+        ParseErrorKind::MissingRequiredArgument
+    );
+    assert!(!ran);
+}
+
+#[test]
+fn requires_satisfied_runs_the_handler() {
+    let mut app = app(&["--model", "m", "--b", "x", "run"]);
+    app.add_argument("--b", Arg::new().require_value().optional());
+    app.add_argument(
+        "--model",
+        Arg::new().require_value().optional().requires("--b"),
+    );
+    let (res, ran) = run_action(&mut app);
+    assert_eq!(res.unwrap(), 0);
+    assert!(ran);
+}
+
+#[test]
+fn cross_validators_run_before_the_handler() {
+    let mut app = app(&["--gpu-layers", "8", "run"]);
+    app.add_argument("--gpu-layers", Arg::new().require_value().optional());
+    app.add_cross_validator(|args: &clark::ParsedArg| {
+        match args.find("--gpu-layers").is_some() && args.arg_at(1) != Some("heavy") {
+            true => Err(clark::ParseError::conflicting_argument(format_args!(
+                "--gpu-layers requires action heavy"
+            ))),
+            false => Ok(()),
+        }
+    });
+    let (res, ran) = run_action(&mut app);
+    assert_eq!(res.unwrap_err().kind, ParseErrorKind::ConflictingArgument);
+    assert!(!ran);
+}
+
+#[test]
+fn ordered_args_are_checked_under_action_builder() {
+    let mut app = app(&["--min", "5", "--max", "2", "run"]);
+    app.add_argument("--min", Arg::new().require_value().optional());
+    app.add_argument("--max", Arg::new().require_value().optional());
+    app.add_cross_validator(OrderedArgs::new("--min", "--max"));
+    let (res, ran) = run_action(&mut app);
+    assert_eq!(res.unwrap_err().kind, ParseErrorKind::InvalidValue);
+    assert!(!ran);
+}
+
+#[test]
+fn ordered_args_in_order_run_the_handler() {
+    let mut app = app(&["--min", "2", "--max", "5", "run"]);
+    app.add_argument("--min", Arg::new().require_value().optional());
+    app.add_argument("--max", Arg::new().require_value().optional());
+    app.add_cross_validator(OrderedArgs::new("--min", "--max"));
+    let (res, ran) = run_action(&mut app);
+    assert_eq!(res.unwrap(), 0);
+    assert!(ran);
+}
+
+/* Warns on every post_validate, like a validator that is not idempotent. */
+struct Careful;
+impl ArgValidator for Careful {
+    fn post_validate(
+        &self,
+        _k: Option<&ArgKey>,
+        args: &mut clark::ParsedArg,
+    ) -> Result<(), clark::ParseError> {
+        args.add_warning("careful");
+        Ok(())
+    }
+}
+
+#[test]
+fn warnings_are_reported_once_across_parses() {
+    let mut app = app(&["--x", "run"]);
+    app.add_argument("--x", Arg::new().as_flag().optional().validate(Careful));
+    app.try_parse_args(false).unwrap();
+    let (res, ran) = run_action(&mut app);
+    assert_eq!(res.unwrap(), 0);
+    assert!(ran);
+    assert_eq!(app.warnings(), ["careful"]);
+}
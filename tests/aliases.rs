@@ -0,0 +1,51 @@
+use clark::{App, AppIdentity, AppVersion, Arg, ArgParser, ParseErrorKind};
+
+fn parse(parser: &ArgParser, args: &[&str]) -> clark::ParsedArg {
+    let mut raw = args.iter().map(|arg| arg.to_string()).peekable();
+    parser.parse(&mut raw).unwrap()
+}
+
+#[test]
+fn key_equal_to_an_alias_keeps_the_aliased_argument() {
+    let mut parser = ArgParser::new();
+    parser.add_argument(
+        "--output",
+        Arg::new().require_value().optional().alias("-o"),
+    );
+    parser.add_argument("-o", Arg::new().as_flag().optional());
+    let args = parse(&parser, &["prog", "--output", "x"]);
+    assert_eq!(args.first_of("--output").map(String::as_str), Some("x"));
+}
+
+#[test]
+fn existing_key_is_replaced() {
+    let mut parser = ArgParser::new();
+    parser.add_argument("--output", Arg::new().as_flag().optional().alias("-o"));
+    parser.add_argument("--output", Arg::new().require_value().optional());
+    let args = parse(&parser, &["prog", "--output", "x"]);
+    assert_eq!(args.first_of("--output").map(String::as_str), Some("x"));
+}
+
+#[test]
+fn alias_clash_is_a_definition_error() {
+    let mut app = App::new(AppIdentity::new("test", "", AppVersion::new(1, 0, 0)));
+    app.add_argument(
+        "--output",
+        Arg::new().require_value().optional().alias("-o"),
+    );
+    app.add_argument("-o", Arg::new().as_flag().optional());
+    let err = app.validate_definition().unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::InvalidDefinition);
+    assert_eq!(err.related_keys(), ["-o"]);
+}
+
+#[test]
+fn distinct_aliases_are_valid() {
+    let mut app = App::new(AppIdentity::new("test", "", AppVersion::new(1, 0, 0)));
+    app.add_argument(
+        "--output",
+        Arg::new().require_value().optional().alias("-o"),
+    );
+    app.add_argument("--quiet", Arg::new().as_flag().optional().alias("-q"));
+    assert!(app.validate_definition().is_ok());
+}
@@ -0,0 +1,36 @@
+use clark::{Arg, ArgOptionValidator, ArgParser, ParseErrorKind};
+
+fn parser(options: ArgOptionValidator) -> ArgParser {
+    let mut parser = ArgParser::new();
+    parser.add_argument(
+        "--mode",
+        Arg::new()
+            .require_value()
+            .optional()
+            .validate(options.option("debug", None).option("release", None)),
+    );
+    parser
+}
+
+fn parse(parser: &ArgParser, args: &[&str]) -> Result<Option<String>, clark::ParseError> {
+    let mut raw = args.iter().map(|arg| arg.to_string()).peekable();
+    parser
+        .parse(&mut raw)
+        .map(|args| args.first_of("--mode").cloned())
+}
+
+#[test]
+fn ignore_case_stores_the_declared_option() {
+    let parser = parser(ArgOptionValidator::new().ignore_case());
+    let mode = parse(&parser, &["prog", "--mode", "RELEASE"]).unwrap();
+    assert_eq!(mode.as_deref(), Some("release"));
+}
+
+#[test]
+fn case_matters_by_default() {
+    let parser = parser(ArgOptionValidator::new());
+    let err = parse(&parser, &["prog", "--mode", "Release"]).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::InvalidValue);
+    let mode = parse(&parser, &["prog", "--mode", "debug"]).unwrap();
+    assert_eq!(mode.as_deref(), Some("debug"));
+}
@@ -0,0 +1,38 @@
+use clark::{Arg, ArgParser, ParseError, ParseErrorKind, ParsedArg};
+
+fn parse(parser: &ArgParser, args: &[&str]) -> Result<ParsedArg, ParseError> {
+    let mut raw = args.iter().map(|arg| arg.to_string()).peekable();
+    parser.parse(&mut raw)
+}
+
+fn parser() -> ArgParser {
+    let mut parser = ArgParser::new();
+    parser.add_argument("--b", Arg::new().require_value().with_default("x"));
+    parser.add_argument("--a", Arg::new().as_flag().optional().conflicts_with("--b"));
+    parser.add_argument(
+        "--model",
+        Arg::new().require_value().optional().requires("--b"),
+    );
+    parser
+}
+
+#[test]
+fn conflict_ignores_defaults() {
+    let args = parse(&parser(), &["prog", "--a"]).unwrap();
+    assert_eq!(args.first_of("--b").map(String::as_str), Some("x"));
+}
+
+#[test]
+fn conflict_with_given_key() {
+    let err = parse(&parser(), &["prog", "--a", "--b", "y"]).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::ConflictingArgument);
+    assert_eq!(err.related_keys(), ["--b"]);
+}
+
+#[test]
+fn requires_ignores_defaults() {
+    let err = parse(&parser(), &["prog", "--model", "m"]).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::MissingRequiredArgument);
+    assert_eq!(err.related_keys(), ["--b"]);
+    assert!(parse(&parser(), &["prog", "--model", "m", "--b", "y"]).is_ok());
+}
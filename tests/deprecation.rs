@@ -0,0 +1,25 @@
+use clark::{Arg, ArgParser, DeprecatedArg, ParsedArg};
+
+fn parse(args: &[&str]) -> ParsedArg {
+    let mut parser = ArgParser::new();
+    parser.add_argument(
+        "--old",
+        Arg::new()
+            .require_value()
+            .optional()
+            .deprecated(DeprecatedArg::new("use --new instead")),
+    );
+    let mut raw = args.iter().map(|arg| arg.to_string()).peekable();
+    parser.parse(&mut raw).unwrap()
+}
+
+#[test]
+fn warns_when_given() {
+    let args = parse(&["prog", "--old", "x"]);
+    assert_eq!(args.warnings(), ["--old: deprecated: use --new instead"]);
+}
+
+#[test]
+fn quiet_when_not_given() {
+    assert!(parse(&["prog"]).warnings().is_empty());
+}
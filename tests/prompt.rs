@@ -0,0 +1,107 @@
+use std::io::Cursor;
+
+use clark::{
+    App, AppIdentity, AppVersion, Arg, ArgOptionValidator, ConfirmDefault, OutputCapture,
+    ParseErrorKind, ValueSource,
+};
+
+fn app(input: &str, out: &OutputCapture) -> App {
+    let mut app = App::new(AppIdentity::new("test", "", AppVersion::new(1, 0, 0)));
+    app.with_args(Vec::<String>::new());
+    app.with_io(out.clone(), Cursor::new(input.to_string()));
+    app.prompt_missing(true);
+    app
+}
+
+#[test]
+fn missing_value_is_read_from_the_injected_io() {
+    let out = OutputCapture::new();
+    let mut app = app("bob\n", &out);
+    app.add_argument("--name", Arg::new().required());
+    app.try_parse_args(false).unwrap();
+    assert_eq!(
+        app.args().first_of("--name").map(String::as_str),
+        Some("bob")
+    );
+    assert_eq!(app.args().source_of("--name"), Some(ValueSource::Prompt));
+    out.assert_contains("Enter value for --name: ");
+}
+
+#[test]
+fn secret_value_is_read_from_the_injected_io() {
+    let out = OutputCapture::new();
+    let mut app = app("hunter2\n", &out);
+    app.add_argument("--token", Arg::new().required().secret());
+    app.try_parse_args(false).unwrap();
+    assert_eq!(
+        app.args().first_of("--token").map(String::as_str),
+        Some("hunter2")
+    );
+    out.assert_contains("Enter value for --token: ");
+    out.assert_not_contains("hunter2");
+}
+
+#[test]
+fn refused_value_is_asked_again() {
+    let out = OutputCapture::new();
+    let mut app = app("fast\nslow\n", &out);
+    app.add_argument(
+        "--mode",
+        Arg::new()
+            .required()
+            .validate(ArgOptionValidator::new().option("slow", None::<String>)),
+    );
+    app.try_parse_args(false).unwrap();
+    assert_eq!(
+        app.args().first_of("--mode").map(String::as_str),
+        Some("slow")
+    );
+}
+
+#[test]
+fn closed_input_fails_the_parse() {
+    let out = OutputCapture::new();
+    let mut app = app("", &out);
+    app.add_argument("--name", Arg::new().required());
+    let err = app.try_parse_args(false).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::NoValueGiven);
+}
+
+/* Asks "Continue?" after parsing args, with -y/--yes registered. */
+fn confirm(args: &[&str], input: &str, default: ConfirmDefault) -> (bool, OutputCapture) {
+    let out = OutputCapture::new();
+    let mut app = App::new(AppIdentity::new("test", "", AppVersion::new(1, 0, 0)));
+    app.with_args(args.iter().copied());
+    app.with_io(out.clone(), Cursor::new(input.to_string()));
+    app.add_yes_flag();
+    app.try_parse_args(false).unwrap();
+    (app.confirm("Continue?", default).unwrap(), out)
+}
+
+#[test]
+fn confirm_reads_the_answer() {
+    assert!(confirm(&[], "y\n", ConfirmDefault::No).0);
+    assert!(!confirm(&[], "n\n", ConfirmDefault::Yes).0);
+}
+
+#[test]
+fn confirm_empty_answer_takes_the_default() {
+    assert!(confirm(&[], "\n", ConfirmDefault::Yes).0);
+    assert!(!confirm(&[], "\n", ConfirmDefault::No).0);
+}
+
+#[test]
+fn confirm_asks_again_on_other_answers() {
+    let (answer, out) = confirm(&[], "maybe\nyes\n", ConfirmDefault::No);
+    assert!(answer);
+    out.assert_contains("Please answer y or n.");
+}
+
+#[test]
+fn confirm_yes_flag_skips_the_question() {
+    for flag in ["--yes", "-y"] {
+        let (answer, out) = confirm(&[flag], "n\n", ConfirmDefault::No);
+        assert!(answer);
+        out.assert_not_contains("Continue?");
+    }
+}
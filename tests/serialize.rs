@@ -0,0 +1,22 @@
+#![cfg(feature = "serde")]
+
+use clark::{Arg, ArgParser, ParsedArg};
+
+fn parse(args: &[&str]) -> ParsedArg {
+    let mut parser = ArgParser::new();
+    parser.add_argument("--user", Arg::new().require_value().optional());
+    parser.add_argument("--token", Arg::new().require_value().optional().secret());
+    let mut raw = args.iter().map(|arg| arg.to_string()).peekable();
+    parser.parse(&mut raw).unwrap()
+}
+
+#[test]
+fn secret_values_are_masked() {
+    let args = parse(&["prog", "--user", "ada", "--token", "hunter2"]);
+    let json = serde_json::to_value(&args).unwrap();
+    let params = &json["tiers"][0]["params"];
+    assert_eq!(params[0]["value"], "ada");
+    assert_eq!(params[1]["key"], "--token");
+    assert_eq!(params[1]["value"], "***");
+    assert!(!json.to_string().contains("hunter2"));
+}